@@ -0,0 +1,98 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is normal and var2 is normal then output is normal")
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is medium and var2 is somewhat high then output is medium")
+        .rule("if var1 is critical and var2 is somewhat high then output is high")
+        .rule("if var1 is critical or var2 is critical then output is critical")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+
+    let coverage = f_engine.coverage(21, 0.1);
+    println!("Complete: {}, lowest strength: {}", coverage.is_complete(), coverage.min_strength);
+    for gap in coverage.gaps.iter().take(5) {
+        println!("Gap: {:?} (strength {})", gap.bounds, gap.strength);
+    }
+    println!("{} gaps", coverage.gaps.len());
+    for combination in &coverage.unreferenced {
+        println!("No rule for: {}", combination.join(", "));
+    }
+
+    for issue in f_engine.lint() {
+        println!("{}", issue);
+    }
+
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "low", vec![20.0, 50.0]),
+            ("trapezoid", "mid", vec![20.0, 40.0, 60.0, 80.0]),
+            ("up", "high", vec![50.0, 80.0]),
+            ("up", "beyond", vec![120.0, 150.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "low", vec![20.0, 50.0]),
+            ("up", "high", vec![50.0, 80.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "low", vec![20.0, 50.0]),
+            ("trapezoid", "mid", vec![20.0, 40.0, 60.0, 80.0]),
+            ("up", "high", vec![50.0, 80.0])
+        ], 0, 100)
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is low and var2 is low then output is low")
+        .rule("if var1 is mid then output is mid")
+        .rule("if var1 is mid then output is mid")
+        .rule("if var1 is high then output is high")
+        .rule("if var1 is high then output is low")
+        .rule("if var1 is low and var1 is high then output is mid")
+        .rule("if var1 is beyond then output is high")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+
+    let linter = rsfuzzy::Linter::new().conflict_distance(0.5);
+    for issue in linter.lint(&f_engine) {
+        println!("{}", issue);
+    }
+
+    for report in f_engine.partition_reports(101) {
+        println!("{}: coverage {}, uncovered {:?}, epsilon {}, Ruspini error {}, ordered {}, degenerate {:?}, outside {:?}",
+            report.var, report.coverage, report.uncovered, report.epsilon, report.ruspini_error, report.ordered, report.degenerate, report.outside);
+        println!("    overlap: {:?}", report.overlap);
+    }
+
+    let names = ["low", "medium", "high"];
+    let partitions = [
+        ("triangles", rsfuzzy::InputVar::<f32>::uniform_triangles(&names, 0, 100)),
+        ("trapezoids", rsfuzzy::InputVar::uniform_trapezoids(&names, 0, 100)),
+        ("gaussians", rsfuzzy::InputVar::uniform_gaussians(&names, 0, 100)),
+        ("shouldered", rsfuzzy::InputVar::uniform_shouldered(&names, 0, 100))
+    ];
+    for (name, var) in partitions.iter() {
+        let report = var.partition_report(name, 101);
+        println!("{}: Ruspini {}, epsilon {}, valid {}", name, report.is_ruspini(1e-5), report.epsilon, report.is_valid());
+    }
+}
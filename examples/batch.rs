@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "normal", vec![0.0, 50.0]),
+            ("up", "critical", vec![50.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is normal and var2 is normal then output is normal")
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is critical or var2 is critical then output is critical")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+
+    let rows = vec![
+        10.0, 20.0,
+        35.0, 20.0,
+        80.0, 90.0
+    ];
+    let results = f_engine.calculate_batch(&rows, rsfuzzy::Layout::RowMajor);
+    println!("Results: {:?}", results);
+
+    let columns = vec![
+        10.0, 35.0, 80.0,
+        20.0, 20.0, 90.0
+    ];
+    let results = f_engine.calculate_batch(&columns, rsfuzzy::Layout::ColumnMajor);
+    println!("Results: {:?}", results);
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate rsfuzzy;
+use std::collections::HashMap;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is normal then output is normal")
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is medium then output is medium")
+        .rule("if var1 is high then output is high")
+        .rule("if var1 is critical then output is critical")
+        .rule("if var1 is mildly critical then output is very^1.5 critical")
+        .hedge_fn("mildly", |x: f32| x.powf(0.8))
+        .input_policy("var1", rsfuzzy::InputPolicy {
+            range: rsfuzzy::RangePolicy::Clamp,
+            missing: rsfuzzy::MissingPolicy::Unknown(0.5)
+        })
+        .defuzz("centroid")
+        .build();
+
+    let f_engine = match f_engine {
+        Ok(engine) => engine,
+        Err(e) => panic!("{}", e)
+    };
+
+    let inputs = fz_set_inputs![
+        ("var1", 21.2)
+    ];
+
+    let result = f_engine.calculate(inputs);
+    println!("Result: {:?}", result);
+
+    let mut scratch = f_engine.scratch();
+    println!("Clamped: {:?}", f_engine.evaluate(&[150.0], &mut scratch));
+    println!("Unknown: {:?}", f_engine.evaluate(&[f32::NAN], &mut scratch));
+
+    println!("var1: {:?}", f_engine.fuzzify("var1", 21.2).unwrap());
+    let very = rsfuzzy::hedges::Hedge::new("very", None);
+    println!("var1 (very): {:?}", f_engine.fuzzify_with("var1", 21.2, &very).unwrap());
+}
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate rsfuzzy;
+use std::collections::HashMap;
+
+static CONFIG: &str = "
+# Risk scoring
+input var1 [0, 100]
+    normal: down(0, 30)
+    low: triangle(15, 30, 40)
+    medium: triangle(30, 40, 55)
+    high: triangle(40, 60, 75)
+    critical: up(60, 100)
+
+input var2 [0, 100]
+    normal: down(0, 30)
+    low: triangle(15, 30, 40)
+    medium: triangle(30, 40, 55)
+    high: triangle(40, 60, 75)
+    critical: up(60, 100)
+
+output output [0, 100]
+    normal: down(0, 30)
+    low: triangle(15, 30, 40)
+    medium: triangle(30, 40, 55)
+    high: triangle(40, 60, 75)
+    critical: up(60, 100)
+
+defuzz centroid
+
+operators
+    and: min
+    or: max
+
+rules
+    if var1 is normal and var2 is normal then output is normal
+    if var1 is very low and var2 is normal then output is very low
+    if var1 is low then output is low
+    if var1 is medium then output is medium
+    if var1 is high then output is high
+    if var1 is critical then output is critical
+    if var1 is low and var2 is high then output is medium
+";
+
+pub fn main() {
+    let f_engine = match rsfuzzy::Engine::from_config(CONFIG) {
+        Ok(engine) => engine,
+        Err(e) => panic!("{}", e)
+    };
+
+    let inputs = fz_set_inputs![
+        ("var1", 21.2),
+        ("var2", 61.5)
+    ];
+
+    let result = f_engine.calculate(inputs);
+    println!("Result: {:?}", result);
+
+    if let Err(e) = rsfuzzy::Engine::from_config("input var1 [0, 100]\n    low: triangle(15, 30)\n") {
+        println!("Error: {}", e);
+    }
+}
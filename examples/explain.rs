@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+            ("triangle", "high", vec![40.0, 60.0, 75.0]),
+            ("up", "critical", vec![60.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is normal and var2 is normal then output is normal")
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is medium and var2 is somewhat high then output is medium")
+        .rule("if var1 is critical and var2 is somewhat high then output is high")
+        .rule("if var1 is critical or var2 is critical then output is critical")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+
+    let trace = f_engine.calculate_explained(&[80.0, 52.0]).unwrap();
+    println!("{}", trace.explain());
+
+    let output = f_engine.output_var("output").unwrap();
+    println!("Set: {:?}", output.approximate(&f_engine, &trace.aggregate));
+    println!("Value {}: {:?}", trace.output.value, output.approximate_value(&f_engine, trace.output.value));
+
+    let set = f_engine.infer(&[80.0, 52.0]).unwrap();
+    println!("Centroid: {}, mean of maxima: {}, bisector: {}", set.centroid(), set.mean_of_maxima(), set.bisector());
+    println!("Height: {}, support: {:?}", set.height(), set.support());
+
+    let mut scratch = f_engine.scratch();
+    println!("Outcome: {:?}", f_engine.evaluate(&[80.0, 52.0], &mut scratch));
+
+    let explainer = rsfuzzy::Explainer::new().top(1).precision(3);
+    println!("{}", explainer.explain(&f_engine.calculate_explained(&[21.2, 61.5]).unwrap()));
+}
@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+use rsfuzzy::Q16;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "normal", vec![0.0, 50.0]),
+            ("up", "critical", vec![50.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is very normal and var2 is normal then output is normal")
+        .rule("if var1 is somewhat low then output is low")
+        .rule("if var1 is critical or var2 is critical then output is very critical")
+        .rule("if var1 is more or less low and var2 is not critical then output is indeed low")
+        .rule("if var1 is above low then output is below critical")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let q_engine = match f_engine.compile_q16() {
+        Ok(engine) => engine,
+        Err(e) => panic!("{}", e)
+    };
+
+    let mut scratch = f_engine.scratch();
+    let mut max_error = 0.0f32;
+    for var1 in 0..100 {
+        for var2 in 0..100 {
+            let (var1, var2) = (var1 as f32, var2 as f32);
+            let expected = f_engine.calculate_into(&[var1, var2], &mut scratch);
+            let result = q_engine.calculate(&[Q16::from_float(var1), Q16::from_float(var2)]);
+            match result {
+                Some(value) => max_error = max_error.max((value.to_float::<f32>() - expected).abs()),
+                None => assert!(expected.is_nan())
+            }
+        }
+    }
+    println!("Result at (35, 20): {}", q_engine.calculate(&[Q16::from_int(35), Q16::from_int(20)]).unwrap());
+    println!("Max error against the float engine: {}", max_error);
+}
@@ -0,0 +1,30 @@
+extern crate rsfuzzy;
+
+pub fn main() {
+    let names = ["low", "medium", "high"];
+    let mut f_engine = rsfuzzy::Engine::new();
+    f_engine.add_input_var("var1", rsfuzzy::InputVar::uniform_shouldered(&names, 0, 100), 0, 100);
+    f_engine.add_input_var("var2", rsfuzzy::InputVar::uniform_shouldered(&names, 0, 100), 0, 100);
+    f_engine.add_output_var("output", rsfuzzy::OutputVar::uniform_triangles(&["lowest", "low", "medium", "high", "highest"], 0, 100), 0, 100);
+    f_engine.add_defuzz("centroid");
+
+    let mut samples = Vec::new();
+    for x1 in (0..=100).step_by(5) {
+        for x2 in (0..=100).step_by(5) {
+            let (x1, x2) = (x1 as f32, x2 as f32);
+            samples.push((vec![x1, x2], (x1 + x2) / 2.0));
+        }
+    }
+
+    let rules = f_engine.generate_rules("output", &samples);
+    for rule in &rules {
+        println!("{} (degree {:.2}, {} samples)", rule.to_rule(), rule.degree, rule.samples);
+    }
+    f_engine.add_generated_rules(&rules);
+
+    let mut scratch = f_engine.scratch();
+    let error = samples.iter()
+        .map(|&(ref inputs, y)| (f_engine.calculate_into(inputs, &mut scratch) - y).abs())
+        .fold(0.0f32, f32::max);
+    println!("Largest error over the samples: {}", error);
+}
@@ -0,0 +1,32 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+pub fn main() {
+    let f_engine = rsfuzzy::Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .input("var2", fz_input_var![
+            ("down", "normal", vec![0.0, 50.0]),
+            ("up", "critical", vec![50.0, 100.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("down", "normal", vec![0.0, 30.0]),
+            ("triangle", "low", vec![15.0, 30.0, 40.0]),
+            ("up", "critical", vec![30.0, 100.0])
+        ], 0, 100)
+        .rule("if var1 is normal and var2 is normal then output is normal")
+        .rule("if var1 is low then output is low")
+        .rule("if var1 is critical or var2 is critical then output is critical")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+
+    for resolution in &[11, 21, 51] {
+        let table = f_engine.compile_lut(*resolution);
+        println!("Resolution {}: result {} max error {}", resolution, table.calculate(&[21.2, 61.5]), table.max_error());
+    }
+    println!("Exact: {}", f_engine.calculate_into(&[21.2, 61.5], &mut f_engine.scratch()));
+}
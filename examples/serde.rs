@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate rsfuzzy;
+extern crate serde_json;
+use std::collections::HashMap;
+
+pub fn main() {
+    let mut f_engine = rsfuzzy::Engine::new();
+
+    let i_var1 = fz_input_var![
+        ("down", "normal", vec![0.0, 30.0]),
+        ("triangle", "low", vec![15.0, 30.0, 40.0]),
+        ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+        ("triangle", "high", vec![40.0, 60.0, 75.0]),
+        ("up", "critical", vec![60.0, 100.0])
+    ];
+    f_engine.add_input_var("var1", i_var1, 0, 100);
+
+    let o_var = fz_output_var![
+        ("down", "normal", vec![0.0, 30.0]),
+        ("triangle", "low", vec![15.0, 30.0, 40.0]),
+        ("triangle", "medium", vec![30.0, 40.0, 55.0]),
+        ("triangle", "high", vec![40.0, 60.0, 75.0]),
+        ("up", "critical", vec![60.0, 100.0])
+    ];
+    f_engine.add_output_var("output", o_var, 0, 100);
+
+    let f_rules = vec![
+        ("if var1 is normal then output is normal"),
+        ("if var1 is very low then output is very low"),
+        ("if var1 is medium then output is medium"),
+        ("if var1 is critical then output is critical"),
+    ];
+
+    f_engine.add_rules(f_rules);
+    f_engine.add_defuzz("centroid");
+
+    let json = serde_json::to_string_pretty(&f_engine).unwrap();
+    println!("{}", json);
+
+    let loaded: rsfuzzy::Engine = serde_json::from_str(&json).unwrap();
+    let inputs = fz_set_inputs![
+        ("var1", 21.2)
+    ];
+
+    let result = loaded.calculate(inputs);
+    println!("Result: {:?}", result);
+
+    let trace = loaded.calculate_explained(&[21.2]).unwrap();
+    println!("{}", serde_json::to_string(&trace).unwrap());
+}
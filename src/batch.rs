@@ -0,0 +1,75 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use alloc::vec::Vec;
+use Engine;
+use float::Float;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    RowMajor,
+    ColumnMajor
+}
+
+impl<T: Float> Engine<T> {
+    // `inputs` holds one value per input variable, ordered by `input_index`, for every row.
+    pub fn calculate_batch(&self, inputs: &[T], layout: Layout) -> Vec<T> {
+        let width = self.input_count();
+        if width == 0 {
+            return Vec::new();
+        }
+        let rows = inputs.len() / width;
+        if rows * width != inputs.len() {
+            panic!("Batch of {} values does not split into rows of {} inputs", inputs.len(), width);
+        }
+        match layout {
+            Layout::RowMajor => self.batch_rows(inputs, width),
+            Layout::ColumnMajor => self.batch_columns(inputs, width, rows)
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn batch_rows(&self, inputs: &[T], width: usize) -> Vec<T> {
+        let mut scratch = self.scratch();
+        inputs.chunks(width).map(|row| self.calculate_into(row, &mut scratch)).collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn batch_rows(&self, inputs: &[T], width: usize) -> Vec<T> {
+        inputs.par_chunks(width)
+            .map_init(|| self.scratch(), |scratch, row| self.calculate_into(row, scratch))
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn batch_columns(&self, inputs: &[T], width: usize, rows: usize) -> Vec<T> {
+        let mut scratch = self.scratch();
+        let mut row = vec![T::zero(); width];
+        (0..rows).map(|i| {
+            for (j, val) in row.iter_mut().enumerate() {
+                *val = inputs[j * rows + i];
+            }
+            self.calculate_into(&row, &mut scratch)
+        }).collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn batch_columns(&self, inputs: &[T], width: usize, rows: usize) -> Vec<T> {
+        (0..rows).into_par_iter()
+            .map_init(|| (self.scratch(), vec![T::zero(); width]), |state, i| {
+                let (ref mut scratch, ref mut row) = *state;
+                for (j, val) in row.iter_mut().enumerate() {
+                    *val = inputs[j * rows + i];
+                }
+                self.calculate_into(row, scratch)
+            })
+            .collect()
+    }
+}
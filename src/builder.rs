@@ -0,0 +1,229 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+use core::ops::Deref;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use alloc::sync::Arc;
+use deffuz::Operators;
+use hedges::{HedgeKind, HedgeFn};
+use policy::{NoFirePolicy, InputPolicy};
+use float::Float;
+use {Engine, InputVar, OutputVar};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    NoInputs,
+    NoOutputs,
+    NoRules,
+    NoDefuzz,
+    EmptyVar(String),
+    InvalidRange(String, usize, usize),
+    DuplicateVar(String),
+    UnknownVar(String),
+    InvalidDefuzz(String),
+    InvalidOperator(String),
+    InvalidRule(usize, String)
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            BuildError::NoInputs => write!(f, "No input variables defined"),
+            BuildError::NoOutputs => write!(f, "No output variables defined"),
+            BuildError::NoRules => write!(f, "No rules defined"),
+            BuildError::NoDefuzz => write!(f, "No defuzzification type defined"),
+            BuildError::EmptyVar(ref name) => write!(f, "Variable '{}' has no terms", name),
+            BuildError::InvalidRange(ref name, start, end) => write!(f, "Variable '{}' has an empty range [{}, {}]", name, start, end),
+            BuildError::DuplicateVar(ref name) => write!(f, "Variable '{}' is defined more than once", name),
+            BuildError::UnknownVar(ref name) => write!(f, "Variable '{}' is not defined", name),
+            BuildError::InvalidDefuzz(ref name) => write!(f, "Defuzzification type '{}' is not defined", name),
+            BuildError::InvalidOperator(ref msg) => write!(f, "{}", msg),
+            BuildError::InvalidRule(idx, ref msg) => write!(f, "rule {}: {}", idx + 1, msg)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for BuildError {}
+
+// Engine returned by `EngineBuilder::build`. It derefs to `Engine` for evaluation and
+// analysis only: the `add_*` and `set_*` methods need `&mut Engine`, which it never gives out.
+pub struct BuiltEngine<T = f32>(Engine<T>);
+
+impl<T: Float> fmt::Debug for BuiltEngine<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for BuiltEngine<T> {
+    type Target = Engine<T>;
+
+    fn deref(&self) -> &Engine<T> {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Float + Serialize> Serialize for BuiltEngine<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EngineBuilder<T = f32> {
+    inputs: Vec<(String, InputVar<T>, usize, usize)>,
+    outputs: Vec<(String, OutputVar<T>, usize, usize)>,
+    rules: Vec<String>,
+    operators: Vec<(String, String)>,
+    defuzz: Option<String>,
+    no_fire: NoFirePolicy<T>,
+    defaults: Vec<(String, T)>,
+    policies: Vec<(String, InputPolicy<T>)>,
+    hedges: Vec<(String, HedgeKind<T>)>
+}
+
+impl EngineBuilder {
+    pub fn new() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+}
+
+impl<T: Float> EngineBuilder<T> {
+    pub fn input(mut self, name: &str, input_var: InputVar<T>, start: usize, end: usize) -> EngineBuilder<T> {
+        self.inputs.push((name.to_owned(), input_var, start, end));
+        self
+    }
+
+    pub fn output(mut self, name: &str, output_var: OutputVar<T>, start: usize, end: usize) -> EngineBuilder<T> {
+        self.outputs.push((name.to_owned(), output_var, start, end));
+        self
+    }
+
+    pub fn rule(mut self, rule: &str) -> EngineBuilder<T> {
+        self.rules.push(rule.to_owned());
+        self
+    }
+
+    pub fn rules(mut self, rules: Vec<&str>) -> EngineBuilder<T> {
+        self.rules.extend(rules.into_iter().map(|x| x.to_owned()));
+        self
+    }
+
+    pub fn operator(mut self, name: &str, kind: &str) -> EngineBuilder<T> {
+        self.operators.push((name.to_owned(), kind.to_owned()));
+        self
+    }
+
+    pub fn defuzz(mut self, name: &str) -> EngineBuilder<T> {
+        self.defuzz = Some(name.to_owned());
+        self
+    }
+
+    pub fn input_policy(mut self, name: &str, policy: InputPolicy<T>) -> EngineBuilder<T> {
+        self.policies.push((name.to_owned(), policy));
+        self
+    }
+
+    pub fn hedge(mut self, name: &str, kind: HedgeKind<T>) -> EngineBuilder<T> {
+        self.hedges.push((name.to_owned(), kind));
+        self
+    }
+
+    pub fn hedge_fn<F: HedgeFn<T> + 'static>(self, name: &str, f: F) -> EngineBuilder<T> {
+        self.hedge(name, HedgeKind::Custom(Arc::new(f)))
+    }
+
+    pub fn no_fire(mut self, policy: NoFirePolicy<T>) -> EngineBuilder<T> {
+        self.no_fire = policy;
+        self
+    }
+
+    pub fn output_default(mut self, name: &str, value: T) -> EngineBuilder<T> {
+        self.defaults.push((name.to_owned(), value));
+        self
+    }
+
+    pub fn build(self) -> Result<BuiltEngine<T>, BuildError> {
+        if self.inputs.is_empty() {
+            return Err(BuildError::NoInputs);
+        }
+        if self.outputs.is_empty() {
+            return Err(BuildError::NoOutputs);
+        }
+        if self.rules.is_empty() {
+            return Err(BuildError::NoRules);
+        }
+        let defuzz = match self.defuzz {
+            Some(ref name) if name == "centroid" || name == "mom" => name.clone(),
+            Some(name) => return Err(BuildError::InvalidDefuzz(name)),
+            None => return Err(BuildError::NoDefuzz)
+        };
+
+        let mut engine = Engine::default();
+        let mut operators = Operators::default();
+        for (name, kind) in &self.operators {
+            operators.set(name, kind).map_err(BuildError::InvalidOperator)?;
+        }
+        engine.operators = operators;
+        for (name, kind) in self.hedges {
+            engine.add_hedge(&name, kind);
+        }
+
+        for (name, var, start, end) in self.inputs {
+            if engine.inputs.contains_key(&name) {
+                return Err(BuildError::DuplicateVar(name));
+            }
+            if var.vars.is_empty() {
+                return Err(BuildError::EmptyVar(name));
+            }
+            if start >= end {
+                return Err(BuildError::InvalidRange(name, start, end));
+            }
+            engine.add_input_var(&name, var, start, end);
+        }
+        for (name, var, start, end) in self.outputs {
+            if engine.output.contains_key(&name) {
+                return Err(BuildError::DuplicateVar(name));
+            }
+            if var.vars.is_empty() {
+                return Err(BuildError::EmptyVar(name));
+            }
+            if start >= end {
+                return Err(BuildError::InvalidRange(name, start, end));
+            }
+            engine.add_output_var(&name, var, start, end);
+        }
+        for (idx, rule) in self.rules.iter().enumerate() {
+            engine.push_rule(rule).map_err(|e| BuildError::InvalidRule(idx, e))?;
+        }
+        engine.add_defuzz(&defuzz);
+        for (name, policy) in self.policies {
+            if engine.input_index(&name).is_none() {
+                return Err(BuildError::UnknownVar(name));
+            }
+            engine.set_input_policy(&name, policy);
+        }
+        for (name, value) in self.defaults {
+            if !engine.output.contains_key(&name) {
+                return Err(BuildError::UnknownVar(name));
+            }
+            engine.set_output_default(&name, value);
+        }
+        engine.set_no_fire_policy(self.no_fire);
+        Ok(BuiltEngine(engine))
+    }
+}
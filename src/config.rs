@@ -0,0 +1,238 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use mf;
+use float::Float;
+use {Engine, InputVar, OutputVar};
+
+// Native config format:
+//
+//     input var1 [0, 100]
+//         low: triangle(15, 30, 40)
+//     output risk [0, 100]
+//         high: up(60, 100)
+//     defuzz centroid
+//     operators
+//         and: min
+//         or: max
+//     rules
+//         if var1 is low then risk is high
+//
+// Lines starting with '#' are comments, indentation is not significant.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String
+}
+
+impl ParseError {
+    fn new(line: usize, message: String) -> ParseError {
+        ParseError {
+            line,
+            message
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseError {}
+
+enum Section {
+    None,
+    Input(usize),
+    Output(usize),
+    Operators,
+    Rules
+}
+
+struct VarDef<T> {
+    line: usize,
+    name: String,
+    start: usize,
+    end: usize,
+    terms: Vec<mf::MfType<T>>
+}
+
+pub fn parse<T: Float>(src: &str) -> Result<Engine<T>, ParseError> {
+    let mut inputs: Vec<VarDef<T>> = Vec::new();
+    let mut outputs: Vec<VarDef<T>> = Vec::new();
+    let mut operators: Vec<(usize, String, String)> = Vec::new();
+    let mut rules: Vec<(usize, &str)> = Vec::new();
+    let mut defuzz: Option<(usize, &str)> = None;
+    let mut section = Section::None;
+
+    for (idx, raw) in src.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let keyword = line.split_whitespace().next().unwrap_or("");
+        let rest = line[keyword.len()..].trim();
+
+        if !line.contains(':') {
+            match keyword {
+                "input" => {
+                    let var = parse_var(line_no, rest)?;
+                    if inputs.iter().any(|x| x.name == var.name) {
+                        return Err(ParseError::new(line_no, format!("Variable '{}' is defined more than once", var.name)));
+                    }
+                    inputs.push(var);
+                    section = Section::Input(inputs.len() - 1);
+                    continue;
+                },
+                "output" => {
+                    let var = parse_var(line_no, rest)?;
+                    if outputs.iter().any(|x| x.name == var.name) {
+                        return Err(ParseError::new(line_no, format!("Variable '{}' is defined more than once", var.name)));
+                    }
+                    outputs.push(var);
+                    section = Section::Output(outputs.len() - 1);
+                    continue;
+                },
+                "defuzz" => {
+                    if rest.is_empty() {
+                        return Err(ParseError::new(line_no, "Defuzzification type missing".to_owned()));
+                    }
+                    defuzz = Some((line_no, rest));
+                    section = Section::None;
+                    continue;
+                },
+                "operators" if rest.is_empty() => {
+                    section = Section::Operators;
+                    continue;
+                },
+                "rules" if rest.is_empty() => {
+                    section = Section::Rules;
+                    continue;
+                },
+                _ => ()
+            }
+        }
+
+        match section {
+            Section::Input(i) => inputs[i].terms.push(parse_term(line_no, line)?),
+            Section::Output(i) => outputs[i].terms.push(parse_term(line_no, line)?),
+            Section::Operators => {
+                let (name, kind) = split_pair(line_no, line)?;
+                operators.push((line_no, name.to_owned(), kind.to_owned()));
+            },
+            Section::Rules => rules.push((line_no, line)),
+            Section::None => return Err(ParseError::new(line_no, format!("Unexpected line '{}' outside of a section", line)))
+        }
+    }
+
+    // Reported at the end of the file, where the missing line would go.
+    let (defuzz_line, defuzz) = match defuzz {
+        Some(x) => x,
+        None => return Err(ParseError::new(src.lines().count(), "No defuzzification type defined".to_owned()))
+    };
+    if defuzz != "centroid" && defuzz != "mom" {
+        return Err(ParseError::new(defuzz_line, format!("Defuzzification type '{}' is not defined", defuzz)));
+    }
+
+    if let Some(var) = inputs.iter().chain(outputs.iter()).find(|x| x.terms.is_empty()) {
+        return Err(ParseError::new(var.line, format!("Variable '{}' has no terms", var.name)));
+    }
+
+    let mut engine = Engine::default();
+    for var in inputs {
+        engine.add_input_var(&var.name, InputVar::new(var.terms), var.start, var.end);
+    }
+    for var in outputs {
+        engine.add_output_var(&var.name, OutputVar::new(var.terms), var.start, var.end);
+    }
+    for (line_no, name, kind) in operators {
+        engine.operators.set(&name, &kind).map_err(|e| ParseError::new(line_no, e))?;
+    }
+    for (line_no, rule) in rules {
+        engine.push_rule(rule).map_err(|e| ParseError::new(line_no, e))?;
+    }
+    engine.add_defuzz(defuzz);
+    Ok(engine)
+}
+
+fn split_pair(line_no: usize, line: &str) -> Result<(&str, &str), ParseError> {
+    let mut parts = line.splitn(2, ':');
+    let name = parts.next().unwrap_or("").trim();
+    match parts.next() {
+        Some(value) if !name.is_empty() => Ok((name, value.trim())),
+        _ => Err(ParseError::new(line_no, format!("Invalid syntax. Expected 'name: value' in '{}'", line)))
+    }
+}
+
+fn parse_var<T>(line_no: usize, decl: &str) -> Result<VarDef<T>, ParseError> {
+    let invalid = || ParseError::new(line_no, format!("Invalid syntax. Expected 'name [start, end]' in '{}'", decl));
+    let open = decl.find('[').ok_or_else(invalid)?;
+    let name = decl[..open].trim();
+    if name.is_empty() || name.contains(char::is_whitespace) || !decl.ends_with(']') {
+        return Err(invalid());
+    }
+    let bounds: Vec<&str> = decl[open + 1..decl.len() - 1].split(',').map(|x| x.trim()).collect();
+    if bounds.len() != 2 {
+        return Err(invalid());
+    }
+    let start = bounds[0].parse::<usize>().map_err(|_| invalid())?;
+    let end = bounds[1].parse::<usize>().map_err(|_| invalid())?;
+    if start >= end {
+        return Err(ParseError::new(line_no, format!("Variable '{}' has an empty range [{}, {}]", name, start, end)));
+    }
+    Ok(VarDef {
+        line: line_no,
+        name: name.to_owned(),
+        start,
+        end,
+        terms: Vec::new()
+    })
+}
+
+fn parse_term<T: Float>(line_no: usize, line: &str) -> Result<mf::MfType<T>, ParseError> {
+    let (name, shape) = split_pair(line_no, line)?;
+    let invalid = || ParseError::new(line_no, format!("Invalid syntax. Expected 'kind(params)' in '{}'", shape));
+    let open = shape.find('(').ok_or_else(invalid)?;
+    if !shape.ends_with(')') {
+        return Err(invalid());
+    }
+    let kind = shape[..open].trim();
+    let mut params = Vec::new();
+    for value in shape[open + 1..shape.len() - 1].split(',') {
+        match value.trim().parse::<f64>() {
+            Ok(value) => params.push(T::from_f64(value)),
+            Err(_) => return Err(ParseError::new(line_no, format!("Invalid number '{}' in '{}'", value.trim(), shape)))
+        }
+    }
+    let arity = match kind {
+        "triangle" => 3,
+        "trapezoid" => 4,
+        "up" | "down" | "gaussian" => 2,
+        _ => return Err(ParseError::new(line_no, format!("No MF found for type: {}", kind)))
+    };
+    if params.len() != arity {
+        return Err(ParseError::new(line_no, format!("init var for {} needs {} values", kind, arity)));
+    }
+    Ok(match kind {
+        "triangle" => mf::Triangle::new(name, params),
+        "trapezoid" => mf::Trapezoid::new(name, params),
+        "up" => mf::Up::new(name, params),
+        "gaussian" => mf::Gaussian::new(name, params),
+        _ => mf::Down::new(name, params)
+    })
+}
@@ -0,0 +1,250 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+use deffuz::{DefuzzType, DefuzzOp, Operators};
+use float::Float;
+use mf::Shape;
+use hedges::Hedge;
+use alloc::boxed::Box;
+use Engine;
+
+// Fixed-capacity rule base that needs no heap: at most `R` rules of at most `A`
+// antecedents each, over `I` inputs. Only power hedges are supported, stored as their exponent.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerError {
+    TooManyRules,
+    TooManyAntecedents,
+    InvalidInput(usize),
+    UndefinedDefuzz,
+    UnsupportedHedge
+}
+
+impl fmt::Display for ControllerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ControllerError::TooManyRules => write!(f, "Rule capacity exceeded"),
+            ControllerError::TooManyAntecedents => write!(f, "Antecedent capacity exceeded"),
+            ControllerError::InvalidInput(idx) => write!(f, "Input index {} out of range", idx),
+            ControllerError::UndefinedDefuzz => write!(f, "Cannot use 'Undefined' to defuzzify!"),
+            ControllerError::UnsupportedHedge => write!(f, "Only power hedges can be compiled")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ControllerError {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FixedAntecedent<T = f32> {
+    pub input: usize,
+    pub shape: Shape<T>,
+    pub hedge: Option<T>,
+    pub op: Option<DefuzzOp>
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FixedRule<T, const A: usize> {
+    antecedents: [FixedAntecedent<T>; A],
+    len: usize,
+    output: Shape<T>,
+    hedge: Option<T>
+}
+
+impl<T: Float, const A: usize> FixedRule<T, A> {
+    pub fn new(output: Shape<T>, hedge: Option<T>) -> FixedRule<T, A> {
+        let empty = FixedAntecedent {
+            input: 0,
+            shape: output,
+            hedge: None,
+            op: None
+        };
+        FixedRule {
+            antecedents: [empty; A],
+            len: 0,
+            output,
+            hedge
+        }
+    }
+
+    pub fn push(&mut self, antecedent: FixedAntecedent<T>) -> Result<(), ControllerError> {
+        if self.len == A {
+            return Err(ControllerError::TooManyAntecedents);
+        }
+        self.antecedents[self.len] = antecedent;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn memberships(&self, inputs: &[T]) -> [T; A] {
+        let mut values = [T::zero(); A];
+        for (val, ant) in values.iter_mut().zip(self.antecedents[..self.len].iter()) {
+            *val = ant.shape.compute(inputs[ant.input]);
+            if let Some(p) = ant.hedge {
+                *val = val.powf(p);
+            }
+        }
+        values
+    }
+
+    fn strength(&self, values: &[T; A], ops: &Operators) -> T {
+        let mut val = T::zero();
+        let mut prev_val = T::zero();
+        let mut oper = None;
+        for (idx, ant) in self.antecedents[..self.len].iter().enumerate() {
+            val = values[idx];
+            if let Some(ref op) = oper {
+                val = ops.apply(op, prev_val, val);
+            }
+            oper = ant.op;
+            prev_val = val;
+        }
+        val
+    }
+
+    // Output MF at `y`, hedged.
+    fn sample(&self, y: T) -> T {
+        match self.hedge {
+            Some(p) => self.output.compute(y).powf(p),
+            None => self.output.compute(y)
+        }
+    }
+
+    fn activation(&self, values: &[T; A], sample: T, ops: &Operators) -> T {
+        let mut prev_val = T::zero();
+        let mut oper = None;
+        let mut output = T::zero();
+        for (idx, ant) in self.antecedents[..self.len].iter().enumerate() {
+            output = sample * values[idx];
+            if let Some(ref op) = oper {
+                output = ops.apply(op, prev_val, output);
+            }
+            oper = ant.op;
+            prev_val = output;
+        }
+        output
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Controller<T, const I: usize, const R: usize, const A: usize> {
+    rules: [FixedRule<T, A>; R],
+    len: usize,
+    start: usize,
+    end: usize,
+    defuzz: DefuzzType,
+    operators: Operators
+}
+
+impl<T: Float, const I: usize, const R: usize, const A: usize> Controller<T, I, R, A> {
+    pub fn new(start: usize, end: usize, defuzz: DefuzzType, operators: Operators) -> Result<Controller<T, I, R, A>, ControllerError> {
+        if let DefuzzType::Undefined = defuzz {
+            return Err(ControllerError::UndefinedDefuzz);
+        }
+        let empty = FixedRule::new(Shape::Up(T::zero(), T::zero()), None);
+        Ok(Controller {
+            rules: [empty; R],
+            len: 0,
+            start,
+            end,
+            defuzz,
+            operators
+        })
+    }
+
+    pub fn push(&mut self, rule: FixedRule<T, A>) -> Result<(), ControllerError> {
+        if self.len == R {
+            return Err(ControllerError::TooManyRules);
+        }
+        if let Some(ant) = rule.antecedents[..rule.len].iter().find(|ant| ant.input >= I) {
+            return Err(ControllerError::InvalidInput(ant.input));
+        }
+        self.rules[self.len] = rule;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.len
+    }
+
+    pub fn calculate(&self, inputs: &[T; I]) -> T {
+        match self.defuzz {
+            DefuzzType::Mom(_) => self.mom(inputs),
+            _ => self.centroid(inputs)
+        }
+    }
+
+    fn centroid(&self, inputs: &[T; I]) -> T {
+        let mut first = T::zero();
+        let mut second = T::zero();
+        for rule in &self.rules[..self.len] {
+            let values = rule.memberships(inputs);
+            for y in self.start..self.end {
+                let fdom = rule.activation(&values, rule.sample(T::from_usize(y)), &self.operators);
+                first += T::from_usize(y) * fdom;
+                second += fdom;
+            }
+        }
+        first / second
+    }
+
+    fn mom(&self, inputs: &[T; I]) -> T {
+        let mut sum_result = T::zero();
+        let mut count = 0usize;
+        for rule in &self.rules[..self.len] {
+            let strength = rule.strength(&rule.memberships(inputs), &self.operators);
+            let mut xmax = T::zero();
+            for y in self.start..self.end {
+                xmax = xmax.max(rule.sample(T::from_usize(y)) * strength);
+            }
+            for y in self.start..self.end {
+                let x = rule.sample(T::from_usize(y)) * strength;
+                if x > T::zero() && x == xmax {
+                    sum_result += T::from_usize(y);
+                    count += 1;
+                }
+            }
+        }
+        sum_result / T::from_usize(count)
+    }
+}
+
+fn exponent<T: Float>(hedge: &Option<Box<Hedge<T>>>) -> Result<Option<T>, ControllerError> {
+    match *hedge {
+        Some(ref hedge) => hedge.exponent().map(Some).ok_or(ControllerError::UnsupportedHedge),
+        None => Ok(None)
+    }
+}
+
+impl<T: Float> Engine<T> {
+    pub fn compile_fixed<const I: usize, const R: usize, const A: usize>(&self) -> Result<Controller<T, I, R, A>, ControllerError> {
+        let (start, end) = self.range;
+        let mut controller = Controller::new(start, end, self.defuzz, self.operators)?;
+        for rule in &self.rules {
+            let mut fixed = FixedRule::new(rule.2.shape(), exponent(&rule.1)?);
+            for var in &rule.0 {
+                let input = match self.input_index(&var.0) {
+                    Some(idx) => idx,
+                    None => return Err(ControllerError::InvalidInput(I))
+                };
+                fixed.push(FixedAntecedent {
+                    input,
+                    shape: var.1.shape(),
+                    hedge: exponent(&var.2)?,
+                    op: var.3
+                })?;
+            }
+            controller.push(fixed)?;
+        }
+        Ok(controller)
+    }
+}
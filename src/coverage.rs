@@ -0,0 +1,145 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use deffuz::DefuzzOp;
+use hedges::HedgeKind;
+use float::Float;
+use Engine;
+
+// Part of the input space where no rule fires above the threshold. `bounds` holds an
+// interval per input, indexed as returned by `Engine::input_index`, and `strength` the
+// highest firing strength found in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gap<T = f32> {
+    pub bounds: Vec<(T, T)>,
+    pub strength: T
+}
+
+#[derive(Debug, Clone)]
+pub struct Coverage<T = f32> {
+    pub resolution: usize,
+    pub threshold: T,
+    // Lowest of the maximum firing strengths over the grid.
+    pub min_strength: T,
+    pub gaps: Vec<Gap<T>>,
+    // Term of every input, in `input_index` order, for combinations no rule matches.
+    pub unreferenced: Vec<Vec<String>>
+}
+
+impl<T: Float> Coverage<T> {
+    pub fn is_complete(&self) -> bool {
+        self.gaps.is_empty() && self.unreferenced.is_empty()
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // Samples `resolution` points per input over the universes given to `add_input_var`,
+    // as `compile_lut` does. Neighbouring gaps along the last input are merged.
+    pub fn coverage(&self, resolution: usize, threshold: T) -> Coverage<T> {
+        if resolution < 2 {
+            panic!("Coverage resolution must be at least 2");
+        }
+        let dims = self.universes.len();
+        let mut scratch = self.scratch();
+        let mut point = vec![T::zero(); dims];
+        let mut bounds = vec![(T::zero(), T::zero()); dims];
+        let mut coverage = Coverage {
+            resolution,
+            threshold,
+            min_strength: T::one(),
+            gaps: Vec::new(),
+            unreferenced: self.unreferenced()
+        };
+        let mut open = false;
+        let size = resolution.pow(dims as u32);
+        for idx in 0..size {
+            let mut rest = idx;
+            for dim in (0..dims).rev() {
+                let (start, end) = self.universes[dim];
+                let half = (end - start) / T::from_usize(2 * (resolution - 1));
+                let x = start + (end - start) * T::from_usize(rest % resolution) / T::from_usize(resolution - 1);
+                point[dim] = x;
+                bounds[dim] = ((x - half).max(start), (x + half).min(end));
+                rest /= resolution;
+            }
+            self.plan.fuzzify(&point, &mut scratch);
+            let strength = (0..self.plan.rule_count())
+                .map(|rule| self.plan.strength(rule, &scratch, &self.operators))
+                .fold(T::zero(), |a, b| a.max(b));
+            coverage.min_strength = coverage.min_strength.min(strength);
+            if strength >= threshold {
+                open = false;
+                continue;
+            }
+            let last = coverage.gaps.last_mut();
+            match last {
+                Some(gap) if open && dims > 0 && idx % resolution != 0 => {
+                    gap.bounds[dims - 1].1 = bounds[dims - 1].1;
+                    gap.strength = gap.strength.max(strength);
+                },
+                _ => coverage.gaps.push(Gap {
+                    bounds: bounds.clone(),
+                    strength
+                })
+            }
+            open = true;
+        }
+        coverage
+    }
+
+    // Combinations of one term per input that no rule matches, taking every antecedent
+    // as crisp: it holds for its own term only, or for every other one under an odd
+    // number of `not` hedges. Other hedges are ignored.
+    pub fn unreferenced(&self) -> Vec<Vec<String>> {
+        let terms: Vec<Vec<&str>> = self.input_names.iter()
+            .map(|name| self.inputs[name].vars.iter().map(|mf| mf.name()).collect())
+            .collect();
+        if terms.iter().any(|x| x.is_empty()) {
+            return Vec::new();
+        }
+        let size = terms.iter().fold(1, |a, x| a * x.len());
+        let mut combination = vec![0; terms.len()];
+        let mut result = Vec::new();
+        for idx in 0..size {
+            let mut rest = idx;
+            for dim in (0..terms.len()).rev() {
+                combination[dim] = rest % terms[dim].len();
+                rest /= terms[dim].len();
+            }
+            let matched = self.rules.iter().any(|rule| {
+                let mut val = false;
+                let mut prev_val = false;
+                let mut oper = &None;
+                for var in &rule.0 {
+                    let dim = self.input_names.iter().position(|x| *x == var.0).unwrap();
+                    let negated = match var.2 {
+                        Some(ref hedge) => hedge.kinds().iter().filter(|x| matches!(**x, HedgeKind::Not)).count() % 2 == 1,
+                        None => false
+                    };
+                    val = (terms[dim][combination[dim]] == var.1.name()) != negated;
+                    val = match *oper {
+                        Some(DefuzzOp::And) => prev_val && val,
+                        Some(DefuzzOp::Or) => prev_val || val,
+                        Some(DefuzzOp::Not) => !prev_val,
+                        None => val
+                    };
+                    oper = &var.3;
+                    prev_val = val;
+                }
+                val
+            });
+            if !matched {
+                result.push(combination.iter().enumerate().map(|(dim, &term)| terms[dim][term].to_owned()).collect());
+            }
+        }
+        result
+    }
+}
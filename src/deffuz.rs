@@ -6,51 +6,197 @@
 //
 // http://www.apache.org/licenses/LICENSE-2.0
 
+#[cfg(feature = "std")]
 use std::collections::HashMap;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::boxed::Box;
 use hedges::Hedge;
 use mf;
+use plan::{Plan, Scratch};
+use float::Float;
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de::Error;
+
+#[derive(Debug, Clone, Copy)]
 pub enum DefuzzType {
     Centroid(Centroid),
     Mom(Mom),
     Undefined
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum DefuzzOp {
     And,
     Or,
     Not
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TNorm {
+    Min,
+    Prod
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SNorm {
+    Max,
+    Probor
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Operators {
+    pub and: TNorm,
+    pub or: SNorm
+}
+
+impl Default for Operators {
+    fn default() -> Operators {
+        Operators {
+            and: TNorm::Min,
+            or: SNorm::Max
+        }
+    }
+}
+
+impl Operators {
+    pub fn set(&mut self, name: &str, kind: &str) -> Result<(), String> {
+        match (name, kind) {
+            ("and", "min") => self.and = TNorm::Min,
+            ("and", "prod") => self.and = TNorm::Prod,
+            ("or", "max") => self.or = SNorm::Max,
+            ("or", "probor") => self.or = SNorm::Probor,
+            ("and", _) | ("or", _) => return Err(format!("Operator type '{}' is not defined for '{}'", kind, name)),
+            _ => return Err(format!("Operator '{}' not found!", name))
+        }
+        Ok(())
+    }
+
+    pub fn apply<T: Float>(&self, op: &DefuzzOp, a: T, b: T) -> T {
+        match *op {
+            DefuzzOp::And => match self.and {
+                TNorm::Min => a.min(b),
+                TNorm::Prod => a * b
+            },
+            DefuzzOp::Or => match self.or {
+                SNorm::Max => a.max(b),
+                SNorm::Probor => a + b - a * b
+            },
+            DefuzzOp::Not => T::one() - a
+        }
+    }
+}
+
+pub type Antecedent<T = f32> = (String, mf::MfType<T>, Option<Box<Hedge<T>>>, Option<DefuzzOp>);
+pub type Rule<T = f32> = (Vec<Antecedent<T>>, Option<Box<Hedge<T>>>, mf::MfType<T>);
+
 
 impl DefuzzType {
-    pub fn get(&self, start: usize, end: usize, rules: &Vec<(Vec<(String, mf::MfType, Option<Box<Hedge>>, Option<DefuzzOp>)>, Option<Box<Hedge>>, mf::MfType)>, inputs: HashMap<String, f32>) -> f32 {
+    pub fn name(&self) -> &str {
+        match *self {
+            DefuzzType::Centroid(_) => "centroid",
+            DefuzzType::Mom(_) => "mom",
+            DefuzzType::Undefined => "undefined"
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>) -> T {
+        self.get_with(start, end, rules, inputs, &Operators::default())
+    }
+
+    pub fn eval<T: Float>(&self, plan: &Plan<T>, scratch: &Scratch<T>, ops: &Operators) -> T {
+        match *self {
+            DefuzzType::Centroid(ref value) => value.eval(plan, scratch, ops),
+            DefuzzType::Mom(ref value) => value.eval(plan, scratch, ops),
+            DefuzzType::Undefined => panic!("Cannot use 'Undefined' to defuzzify!")
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_with<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>, ops: &Operators) -> T {
         match *self {
-            DefuzzType::Centroid(ref value) => value.get(start, end, rules, inputs),
-            DefuzzType::Mom(ref value) => value.get(start, end, rules, inputs),
+            DefuzzType::Centroid(ref value) => value.get_with(start, end, rules, inputs, ops),
+            DefuzzType::Mom(ref value) => value.get_with(start, end, rules, inputs, ops),
             DefuzzType::Undefined => panic!("Cannot use 'Undefined' to defuzzify!")
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "serde")]
+impl Serialize for DefuzzType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DefuzzType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DefuzzType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "centroid" => Ok(DefuzzType::Centroid(Centroid)),
+            "mom" => Ok(DefuzzType::Mom(Mom)),
+            "undefined" => Ok(DefuzzType::Undefined),
+            _ => Err(D::Error::custom(format!("Defuzzification type '{}' is not defined", name)))
+        }
+    }
+}
+
+// Center of sums: the centroid of the rule outputs added together, so where rules
+// overlap they count twice. `FuzzyOutput::centroid` takes the maximum instead.
+#[derive(Debug, Clone, Copy)]
 pub struct Centroid;
 
 impl Centroid {
-    pub fn get(&self, start: usize, end: usize, rules: &Vec<(Vec<(String, mf::MfType, Option<Box<Hedge>>, Option<DefuzzOp>)>, Option<Box<Hedge>>, mf::MfType)>, inputs: HashMap<String, f32>) -> f32 {
+    pub fn eval<T: Float>(&self, plan: &Plan<T>, scratch: &Scratch<T>, ops: &Operators) -> T {
+        let (start, _) = plan.range();
+        let mut first = T::zero();
+        let mut second = T::zero();
+        for idx in 0..plan.sample_count() {
+            let mut fdom = T::zero();
+            for rule in 0..plan.rule_count() {
+                fdom += plan.activation(rule, idx, scratch, ops);
+            }
+            first += T::from_usize(start + idx) * fdom;
+            second += fdom;
+        }
+        first / second
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>) -> T {
+        self.get_with(start, end, rules, inputs, &Operators::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_with<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>, ops: &Operators) -> T {
         debug!("Start: {:?}, end: {:?}", start, end);
-        let fdom: Vec<f32> = (start..end).map(|y| {
-            let mut values: Vec<f32> = Vec::with_capacity(rules.len());
+        let fdom: Vec<T> = (start..end).map(|y| {
+            let mut values: Vec<T> = Vec::with_capacity(rules.len());
             for rule in rules {
                 let input_vars = &rule.0;
                 let output_hedge = &rule.1;
                 let output_obj = &rule.2;
+                let at = T::from_usize(y);
+                let sample = match *output_hedge {
+                    Some(ref hedge) => hedge.apply(output_obj.compute(at), output_obj, at),
+                    None => output_obj.compute(at)
+                };
                 let mut val;
-                let mut prev_val = 0f32;
+                let mut prev_val = T::zero();
                 let mut oper = &None;
-                let mut output = 0f32;
+                let mut output = T::zero();
                 for var in input_vars {
                     let input_name = &var.0;
                     let input_obj = &var.1;
@@ -63,44 +209,68 @@ impl Centroid {
                     };
                     val = input_obj.compute(*x);
                     if let Some(ref hedge) = *input_hedge {
-                        val = hedge.compute(val);
-                    }
-                    output = output_obj.compute(y as f32) * val;
-                    if let Some(ref hedge) = *output_hedge {
-                        output = hedge.compute(output);
+                        val = hedge.apply(val, input_obj, *x);
                     }
+                    output = sample * val;
                     if let Some(ref op) = *oper {
-                        output = match *op {
-                            DefuzzOp::And => prev_val.min(output),
-                            DefuzzOp::Or => prev_val.max(output),
-                            DefuzzOp::Not => 1f32 - prev_val
-                        }
+                        output = ops.apply(op, prev_val, output);
                     }
                     oper = operator;
                     prev_val = output;
                 }
                 values.push(output);
             }
-            values.iter().fold(0f32, |a, b| a + b)
+            values.iter().fold(T::zero(), |a, &b| a + b)
         }).collect();
         debug!("Fdom: {:?}", fdom);
         let z_pairs = (start..end).zip(fdom.iter());
-        let map_first = z_pairs.map(|(a, b)| a as f32 * b);
-        let first = map_first.fold(0f32, |a, b| a + b);
-        let second = fdom.iter().fold(0f32, |a, b| a + b);
+        let map_first = z_pairs.map(|(a, b)| T::from_usize(a) * *b);
+        let first = map_first.fold(T::zero(), |a, b| a + b);
+        let second = fdom.iter().fold(T::zero(), |a, &b| a + b);
         first / second
     }
 }
 
-#[derive(Debug, Clone)]
+// Mean of rule maxima: the points where each rule's output peaks, averaged over all
+// rules. `FuzzyOutput::mean_of_maxima` only uses the peak of the aggregated set.
+#[derive(Debug, Clone, Copy)]
 pub struct Mom;
 
 impl Mom {
-    pub fn get(&self, start: usize, end: usize, rules: &Vec<(Vec<(String, mf::MfType, Option<Box<Hedge>>, Option<DefuzzOp>)>, Option<Box<Hedge>>, mf::MfType)>, inputs: HashMap<String, f32>) -> f32 {
-        let mut result: Vec<f32> = Vec::with_capacity(rules.len() + 5);
-        let range: Vec<f32> = (start..end).map(|x| x as f32).collect();
-        let mut values: Vec<(f32, f32)> = Vec::with_capacity(end as usize + 1);
-        let mut xmax: f32;
+    // Mean of the points where each rule's implied set peaks, over all fired rules,
+    // rather than of the peaks of the aggregated set.
+    pub fn eval<T: Float>(&self, plan: &Plan<T>, scratch: &Scratch<T>, ops: &Operators) -> T {
+        let (start, _) = plan.range();
+        let mut sum_result = T::zero();
+        let mut count = 0usize;
+        for rule in 0..plan.rule_count() {
+            let strength = plan.strength(rule, scratch, ops);
+            let mut xmax = T::zero();
+            for idx in 0..plan.sample_count() {
+                xmax = xmax.max(plan.imply(rule, idx, strength));
+            }
+            for idx in 0..plan.sample_count() {
+                let x = plan.imply(rule, idx, strength);
+                if x > T::zero() && x == xmax {
+                    sum_result += T::from_usize(start + idx);
+                    count += 1;
+                }
+            }
+        }
+        sum_result / T::from_usize(count)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>) -> T {
+        self.get_with(start, end, rules, inputs, &Operators::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn get_with<T: Float>(&self, start: usize, end: usize, rules: &[Rule<T>], inputs: HashMap<String, T>, ops: &Operators) -> T {
+        let mut result: Vec<T> = Vec::with_capacity(rules.len() + 5);
+        let range: Vec<T> = (start..end).map(T::from_usize).collect();
+        let mut values: Vec<(T, T)> = Vec::with_capacity(end + 1);
+        let mut xmax: T;
         let mut val;
         let mut prev_val;
         let mut x;
@@ -110,15 +280,15 @@ impl Mom {
         let mut operator;
 
         for rule in rules {
-            xmax = 0f32;
+            xmax = T::zero();
             values.clear();
             let input_vars = &rule.0;
             let output_hedge = &rule.1;
             let output_obj = &rule.2;
 
             for &i in range.iter() {
-                val = 0f32;
-                prev_val = 0f32;
+                val = T::zero();
+                prev_val = T::zero();
                 let mut oper = &None;
                 for var in input_vars {
                     input_name = &var.0;
@@ -133,31 +303,27 @@ impl Mom {
 
                     val = input_obj.compute(*i_crisp);
                     if let Some(ref hedge) = *input_hedge {
-                        val = hedge.compute(val);
+                        val = hedge.apply(val, input_obj, *i_crisp);
                     }
                     if let Some(ref op) = *oper {
-                        val = match *op {
-                            DefuzzOp::And => prev_val.min(val),
-                            DefuzzOp::Or => prev_val.max(val),
-                            DefuzzOp::Not => 1f32 - prev_val
-                        }
+                        val = ops.apply(op, prev_val, val);
                     }
                     oper = operator;
                     prev_val = val;
                 }
-                x = output_obj.compute(i) * val;
-                if let Some(ref hedge) = *output_hedge {
-                    x = hedge.compute(x);
-                }
+                x = match *output_hedge {
+                    Some(ref hedge) => hedge.apply(output_obj.compute(i), output_obj, i),
+                    None => output_obj.compute(i)
+                } * val;
                 if x >= xmax {
                     xmax = x;
                 }
                 values.push((i, x));
             };
 
-            let fdom: Vec<f32> = values.iter().filter_map(|&var| {
+            let fdom: Vec<T> = values.iter().filter_map(|&var| {
                 let (i, x) = var;
-                if x > 0.0 && x == xmax {
+                if x > T::zero() && x == xmax {
                     Some(i)
                 }
                 else {
@@ -167,7 +333,7 @@ impl Mom {
             result.extend(fdom)
         }
 
-        let sum_result = result.iter().fold(0f32, |a, &b| a + b);
-        sum_result / result.len() as f32
+        let sum_result = result.iter().fold(T::zero(), |a, &b| a + b);
+        sum_result / T::from_usize(result.len())
     }
 }
@@ -0,0 +1,109 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt::Write;
+use alloc::vec::Vec;
+use alloc::string::String;
+use deffuz::DefuzzOp;
+use float::Float;
+use trace::{Trace, RuleTrace};
+
+// Renders a `Trace` as a sentence such as
+// "output is high (0.72) mainly because var1 is critical (0.80) and var2 is somewhat high (0.64)".
+// Numbers always use `.` and a fixed number of decimals, whatever the locale.
+#[derive(Debug, Clone, Copy)]
+pub struct Explainer {
+    top: usize,
+    precision: usize
+}
+
+impl Default for Explainer {
+    fn default() -> Explainer {
+        Explainer {
+            top: 3,
+            precision: 2
+        }
+    }
+}
+
+impl Explainer {
+    pub fn new() -> Explainer {
+        Explainer::default()
+    }
+
+    pub fn top(mut self, top: usize) -> Explainer {
+        self.top = top;
+        self
+    }
+
+    pub fn precision(mut self, precision: usize) -> Explainer {
+        self.precision = precision;
+        self
+    }
+
+    pub fn explain<T: Float>(&self, trace: &Trace<T>) -> String {
+        let mut text = String::new();
+        let output = &trace.output;
+        let term = output.terms.iter().fold(None, |best: Option<&(String, T)>, term| match best {
+            Some(best) if best.1 >= term.1 => Some(best),
+            _ => Some(term)
+        });
+        let term = match term {
+            Some(term) if !output.value.is_nan() && term.1 > T::zero() => term,
+            _ => {
+                let _ = write!(text, "{} is undefined because no rule fired", output.name);
+                return text;
+            }
+        };
+        let _ = write!(text, "{} is {} ({:.*})", output.name, term.0, self.precision, term.1.to_f64());
+        for (idx, &(rule, _)) in contributions(trace).iter().take(self.top).enumerate() {
+            text.push_str(if idx == 0 { " mainly because " } else { "; also because " });
+            self.clauses(&mut text, &trace.rules[rule]);
+        }
+        text
+    }
+
+    fn clauses<T: Float>(&self, text: &mut String, rule: &RuleTrace<T>) {
+        let mut oper: Option<DefuzzOp> = None;
+        for ant in &rule.antecedents {
+            if let Some(op) = oper {
+                text.push_str(match op {
+                    DefuzzOp::And => " and ",
+                    DefuzzOp::Or => " or ",
+                    DefuzzOp::Not => " not "
+                });
+            }
+            let _ = write!(text, "{} is ", ant.input);
+            for hedge in &ant.hedges {
+                let _ = write!(text, "{} ", hedge);
+            }
+            let _ = write!(text, "{} ({:.*})", ant.term, self.precision, ant.degree.to_f64());
+            oper = ant.op;
+        }
+    }
+}
+
+// Rules that fired, with their share of the activated output mass, largest first.
+pub fn contributions<T: Float>(trace: &Trace<T>) -> Vec<(usize, T)> {
+    let masses: Vec<T> = trace.rules.iter()
+        .map(|rule| rule.activation.iter().fold(T::zero(), |a, &b| a + b))
+        .collect();
+    let total = masses.iter().fold(T::zero(), |a, &b| a + b);
+    let mut result: Vec<(usize, T)> = masses.into_iter().enumerate()
+        .filter(|&(_, mass)| mass > T::zero())
+        .map(|(idx, mass)| (idx, mass / total))
+        .collect();
+    result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(core::cmp::Ordering::Equal));
+    result
+}
+
+impl<T: Float> Trace<T> {
+    pub fn explain(&self) -> String {
+        Explainer::default().explain(self)
+    }
+}
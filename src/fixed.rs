@@ -0,0 +1,464 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+use core::ops::{Add, Sub, Mul, Div};
+#[cfg(feature = "std")]
+use std::error;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use deffuz::{DefuzzType, DefuzzOp, Operators, TNorm, SNorm};
+use float::Float;
+use mf::Shape;
+use hedges::{Hedge, HedgeKind};
+use alloc::boxed::Box;
+use Engine;
+
+// Q16.16 evaluation path for targets without an FPU. Floats are only used while
+// compiling the engine; `QEngine::calculate` is integer arithmetic throughout.
+
+const LUT_BITS: u32 = 10;
+const LUT_SIZE: usize = (1 << LUT_BITS) + 1;
+// Largest magnitude of a universe bound or MF parameter, so that differences between
+// them, which the MFs divide by, still fit Q16.16.
+const MAX_VALUE: f64 = 16384.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FixedError {
+    UniverseOutOfRange(String),
+    TermOutOfRange(String, String),
+    // Gaussians need `exp`, which has no integer form here.
+    UnsupportedTerm(String, String),
+    UndefinedDefuzz
+}
+
+impl fmt::Display for FixedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            FixedError::UniverseOutOfRange(ref var) => write!(f, "Universe of '{}' does not fit Q16.16 (limit ±{})", var, MAX_VALUE),
+            FixedError::TermOutOfRange(ref var, ref term) => write!(f, "Term '{}' of '{}' does not fit Q16.16 (limit ±{})", term, var, MAX_VALUE),
+            FixedError::UnsupportedTerm(ref var, ref term) => write!(f, "Term '{}' of '{}' cannot be evaluated in Q16.16", term, var),
+            FixedError::UndefinedDefuzz => write!(f, "Cannot use 'Undefined' to defuzzify!")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for FixedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct Q16(pub i32);
+
+impl Q16 {
+    pub const ZERO: Q16 = Q16(0);
+    pub const ONE: Q16 = Q16(1 << 16);
+
+    pub fn from_int(x: i32) -> Q16 {
+        Q16(x << 16)
+    }
+
+    pub fn from_float<T: Float>(x: T) -> Q16 {
+        let x = x.to_f64() * 65536.0;
+        Q16(if x < 0.0 { x - 0.5 } else { x + 0.5 } as i32)
+    }
+
+    pub fn to_float<T: Float>(self) -> T {
+        T::from_f64(self.0 as f64 / 65536.0)
+    }
+
+    pub fn clamp01(self) -> Q16 {
+        self.max(Q16::ZERO).min(Q16::ONE)
+    }
+}
+
+impl Add for Q16 {
+    type Output = Q16;
+
+    fn add(self, other: Q16) -> Q16 {
+        Q16(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Q16 {
+    type Output = Q16;
+
+    fn sub(self, other: Q16) -> Q16 {
+        Q16(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul for Q16 {
+    type Output = Q16;
+
+    fn mul(self, other: Q16) -> Q16 {
+        Q16(((self.0 as i64 * other.0 as i64) >> 16) as i32)
+    }
+}
+
+// Saturates instead of dividing by zero, like the float MFs do with infinities.
+impl Div for Q16 {
+    type Output = Q16;
+
+    fn div(self, other: Q16) -> Q16 {
+        if other.0 == 0 {
+            return match self.0 {
+                0 => Q16::ZERO,
+                x if x > 0 => Q16(i32::MAX),
+                _ => Q16(i32::MIN)
+            };
+        }
+        let q = ((self.0 as i64) << 16) / other.0 as i64;
+        Q16(q.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl fmt::Display for Q16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.0 as f64 / 65536.0)
+    }
+}
+
+pub fn compute(shape: &Shape<Q16>, x: Q16) -> Q16 {
+    match *shape {
+        Shape::Triangle(a, b, c) => {
+            let g1 = (x - a) / (b - a);
+            let g2 = (c - x) / (c - b);
+            g1.min(g2).max(Q16::ZERO)
+        },
+        Shape::Trapezoid(a, b, c, d) => {
+            let g1 = (x - a) / (b - a);
+            let g2 = (d - x) / (d - c);
+            g1.min(g2).clamp01()
+        },
+        Shape::Up(a, b) => up(a, b, x),
+        Shape::Down(a, b) => Q16::ONE - up(a, b, x),
+        Shape::Gaussian(..) => panic!("Gaussian terms cannot be evaluated in Q16.16")
+    }
+}
+
+fn up(a: Q16, b: Q16, x: Q16) -> Q16 {
+    if x < a {
+        return Q16::ZERO;
+    }
+    if x > b {
+        return Q16::ONE;
+    }
+    (x - a) / (b - a)
+}
+
+fn fits<T: Float>(shape: Shape<T>) -> bool {
+    let fit = |x: T| x.to_f64().abs() <= MAX_VALUE;
+    match shape {
+        Shape::Triangle(a, b, c) => fit(a) && fit(b) && fit(c),
+        Shape::Trapezoid(a, b, c, d) => fit(a) && fit(b) && fit(c) && fit(d),
+        Shape::Up(a, b) | Shape::Down(a, b) | Shape::Gaussian(a, b) => fit(a) && fit(b)
+    }
+}
+
+fn shape<T: Float>(shape: Shape<T>) -> Shape<Q16> {
+    match shape {
+        Shape::Triangle(a, b, c) => Shape::Triangle(Q16::from_float(a), Q16::from_float(b), Q16::from_float(c)),
+        Shape::Trapezoid(a, b, c, d) => Shape::Trapezoid(Q16::from_float(a), Q16::from_float(b), Q16::from_float(c), Q16::from_float(d)),
+        Shape::Up(a, b) => Shape::Up(Q16::from_float(a), Q16::from_float(b)),
+        Shape::Down(a, b) => Shape::Down(Q16::from_float(a), Q16::from_float(b)),
+        Shape::Gaussian(mean, sigma) => Shape::Gaussian(Q16::from_float(mean), Q16::from_float(sigma))
+    }
+}
+
+// x^p over [0, 1], sampled at 2^LUT_BITS intervals and linearly interpolated.
+// Tables built with `from_fn` have no exponent.
+#[derive(Debug, Clone)]
+pub struct PowerLut {
+    p: Option<f64>,
+    table: Vec<Q16>
+}
+
+impl PowerLut {
+    pub fn new(p: f64) -> PowerLut {
+        let mut lut = PowerLut::from_fn(|x| Float::powf(x, p));
+        lut.p = Some(p);
+        lut
+    }
+
+    pub fn from_fn<F: Fn(f64) -> f64>(f: F) -> PowerLut {
+        let table = (0..LUT_SIZE).map(|i| Q16::from_float(f(i as f64 / (LUT_SIZE - 1) as f64))).collect();
+        PowerLut {
+            p: None,
+            table
+        }
+    }
+
+    pub fn compute(&self, x: Q16) -> Q16 {
+        let x = x.clamp01().0 as u32;
+        let shift = 16 - LUT_BITS;
+        let idx = (x >> shift) as usize;
+        if idx >= LUT_SIZE - 1 {
+            return self.table[LUT_SIZE - 1];
+        }
+        let frac = (x & ((1 << shift) - 1)) as i64;
+        let (lo, hi) = (self.table[idx].0 as i64, self.table[idx + 1].0 as i64);
+        Q16((lo + (((hi - lo) * frac) >> shift)) as i32)
+    }
+}
+
+// A hedge chain step; powers and custom hedges index the lookup tables, shifting
+// hedges keep the core bound they test against.
+#[derive(Debug, Clone, Copy)]
+enum QHedge {
+    Lut(usize),
+    Not,
+    Indeed,
+    Above(Option<Q16>),
+    Below(Option<Q16>)
+}
+
+#[derive(Debug, Clone)]
+struct QAntecedent {
+    input: usize,
+    shape: Shape<Q16>,
+    hedge: Vec<QHedge>,
+    op: Option<DefuzzOp>
+}
+
+#[derive(Debug, Clone)]
+struct QRule {
+    first: usize,
+    last: usize,
+    output: Shape<Q16>,
+    hedge: Vec<QHedge>
+}
+
+#[derive(Debug, Clone)]
+pub struct QEngine {
+    antecedents: Vec<QAntecedent>,
+    rules: Vec<QRule>,
+    hedges: Vec<PowerLut>,
+    start: usize,
+    end: usize,
+    defuzz: DefuzzType,
+    operators: Operators
+}
+
+impl QEngine {
+    fn lut(&mut self, p: f64) -> usize {
+        match self.hedges.iter().position(|lut| lut.p == Some(p)) {
+            Some(idx) => idx,
+            None => {
+                self.hedges.push(PowerLut::new(p));
+                self.hedges.len() - 1
+            }
+        }
+    }
+
+    // Adjacent powers are merged into one table. Custom hedges are sampled by value
+    // only, so any use they make of the term's shape is lost.
+    fn hedge<T: Float>(&mut self, hedge: &Option<Box<Hedge<T>>>, shape: Shape<Q16>) -> Vec<QHedge> {
+        let kinds = match *hedge {
+            Some(ref hedge) => hedge.kinds(),
+            None => return Vec::new()
+        };
+        let mut chain = Vec::new();
+        let mut power: Option<f64> = None;
+        for kind in kinds {
+            if let HedgeKind::Power(p) = kind {
+                power = Some(power.unwrap_or(1.0) * p.to_f64());
+                continue;
+            }
+            if let Some(p) = power.take() {
+                chain.push(QHedge::Lut(self.lut(p)));
+            }
+            chain.push(match kind {
+                HedgeKind::Not => QHedge::Not,
+                HedgeKind::Indeed => QHedge::Indeed,
+                HedgeKind::Above => QHedge::Above(shape.core().1),
+                HedgeKind::Below => QHedge::Below(shape.core().0),
+                HedgeKind::Power(_) => unreachable!(),
+                HedgeKind::Custom(ref f) => {
+                    self.hedges.push(PowerLut::from_fn(|x| f.compute(T::from_f64(x)).to_f64()));
+                    QHedge::Lut(self.hedges.len() - 1)
+                }
+            });
+        }
+        if let Some(p) = power {
+            chain.push(QHedge::Lut(self.lut(p)));
+        }
+        chain
+    }
+
+    fn apply_hedge(&self, hedge: &[QHedge], x: Q16, at: Q16) -> Q16 {
+        let half = Q16(1 << 15);
+        let two = Q16::from_int(2);
+        hedge.iter().fold(x, |y, step| match *step {
+            QHedge::Lut(idx) => self.hedges[idx].compute(y),
+            QHedge::Not => Q16::ONE - y,
+            QHedge::Indeed if y <= half => two * y * y,
+            QHedge::Indeed => Q16::ONE - two * (Q16::ONE - y) * (Q16::ONE - y),
+            QHedge::Above(Some(end)) if at > end => Q16::ONE - y,
+            QHedge::Below(Some(start)) if at < start => Q16::ONE - y,
+            QHedge::Above(_) | QHedge::Below(_) => Q16::ZERO
+        })
+    }
+
+    fn apply(&self, op: &DefuzzOp, a: Q16, b: Q16) -> Q16 {
+        match *op {
+            DefuzzOp::And => match self.operators.and {
+                TNorm::Min => a.min(b),
+                TNorm::Prod => a * b
+            },
+            DefuzzOp::Or => match self.operators.or {
+                SNorm::Max => a.max(b),
+                SNorm::Probor => a + b - a * b
+            },
+            DefuzzOp::Not => Q16::ONE - a
+        }
+    }
+
+    // Output MF of `rule` at `at`, hedged.
+    fn sample(&self, rule: &QRule, at: Q16) -> Q16 {
+        self.apply_hedge(&rule.hedge, compute(&rule.output, at), at)
+    }
+
+    pub fn antecedent_count(&self) -> usize {
+        self.antecedents.len()
+    }
+
+    pub fn calculate(&self, inputs: &[Q16]) -> Option<Q16> {
+        let mut memberships = vec![Q16::ZERO; self.antecedents.len()];
+        self.calculate_into(inputs, &mut memberships)
+    }
+
+    // `memberships` needs room for `antecedent_count` values. Returns `None` when no rule fires.
+    pub fn calculate_into(&self, inputs: &[Q16], memberships: &mut [Q16]) -> Option<Q16> {
+        for (val, ant) in memberships.iter_mut().zip(self.antecedents.iter()) {
+            let x = inputs[ant.input];
+            *val = self.apply_hedge(&ant.hedge, compute(&ant.shape, x), x);
+        }
+        match self.defuzz {
+            DefuzzType::Mom(_) => self.mom(memberships),
+            _ => self.centroid(memberships)
+        }
+    }
+
+    fn centroid(&self, memberships: &[Q16]) -> Option<Q16> {
+        let mut first = 0i64;
+        let mut second = 0i64;
+        for rule in &self.rules {
+            for y in self.start..self.end {
+                let at = Q16::from_int(y as i32);
+                let sample = self.sample(rule, at);
+                let mut prev_val = Q16::ZERO;
+                let mut oper = None;
+                let mut output = Q16::ZERO;
+                for (val, ant) in memberships[rule.first..rule.last].iter().zip(self.antecedents[rule.first..rule.last].iter()) {
+                    output = sample * *val;
+                    if let Some(ref op) = oper {
+                        output = self.apply(op, prev_val, output);
+                    }
+                    oper = ant.op;
+                    prev_val = output;
+                }
+                first += y as i64 * output.0 as i64;
+                second += output.0 as i64;
+            }
+        }
+        if second <= 0 {
+            return None;
+        }
+        Some(Q16((((first as i128) << 16) / second as i128) as i32))
+    }
+
+    fn mom(&self, memberships: &[Q16]) -> Option<Q16> {
+        let mut sum_result = 0i64;
+        let mut count = 0i64;
+        for rule in &self.rules {
+            let mut val = Q16::ZERO;
+            let mut prev_val = Q16::ZERO;
+            let mut oper = None;
+            for (value, ant) in memberships[rule.first..rule.last].iter().zip(self.antecedents[rule.first..rule.last].iter()) {
+                val = *value;
+                if let Some(ref op) = oper {
+                    val = self.apply(op, prev_val, val);
+                }
+                oper = ant.op;
+                prev_val = val;
+            }
+            let implied = |y: usize| {
+                let at = Q16::from_int(y as i32);
+                self.sample(rule, at) * val
+            };
+            let xmax = (self.start..self.end).map(&implied).max().unwrap_or(Q16::ZERO);
+            for y in self.start..self.end {
+                let x = implied(y);
+                if x > Q16::ZERO && x == xmax {
+                    sum_result += y as i64;
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+        Some(Q16((((sum_result as i128) << 16) / count as i128) as i32))
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // Fails when a universe bound or term parameter lies outside ±16384, a variable
+    // has a Gaussian term, or `add_defuzz` was never called.
+    pub fn compile_q16(&self) -> Result<QEngine, FixedError> {
+        if let DefuzzType::Undefined = self.defuzz {
+            return Err(FixedError::UndefinedDefuzz);
+        }
+        let universes = self.input_names.iter().map(|name| (name, &self.inputs[name].vars, self.inputs[name].start, self.inputs[name].end))
+            .chain(self.output.iter().map(|(name, var)| (name, &var.vars, var.start, var.end)));
+        for (name, vars, start, end) in universes {
+            if start as f64 > MAX_VALUE || end as f64 > MAX_VALUE {
+                return Err(FixedError::UniverseOutOfRange(name.clone()));
+            }
+            if let Some(mf) = vars.iter().find(|mf| matches!(mf.shape(), Shape::Gaussian(..))) {
+                return Err(FixedError::UnsupportedTerm(name.clone(), mf.name().to_owned()));
+            }
+            if let Some(mf) = vars.iter().find(|mf| !fits(mf.shape())) {
+                return Err(FixedError::TermOutOfRange(name.clone(), mf.name().to_owned()));
+            }
+        }
+        let (start, end) = self.range;
+        let mut engine = QEngine {
+            antecedents: Vec::new(),
+            rules: Vec::new(),
+            hedges: Vec::new(),
+            start,
+            end,
+            defuzz: self.defuzz,
+            operators: self.operators
+        };
+        for rule in &self.rules {
+            let first = engine.antecedents.len();
+            for var in &rule.0 {
+                let shape = shape(var.1.shape());
+                let hedge = engine.hedge(&var.2, shape);
+                engine.antecedents.push(QAntecedent {
+                    input: self.input_index(&var.0).unwrap(),
+                    shape,
+                    hedge,
+                    op: var.3
+                });
+            }
+            let output = shape(rule.2.shape());
+            let hedge = engine.hedge(&rule.1, output);
+            engine.rules.push(QRule {
+                first,
+                last: engine.antecedents.len(),
+                output,
+                hedge
+            });
+        }
+        Ok(engine)
+    }
+}
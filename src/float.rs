@@ -0,0 +1,95 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt::{Debug, Display};
+use core::ops::{Add, Sub, Mul, Div, AddAssign};
+
+pub trait Float: Copy + PartialOrd + Debug + Display + Default + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + AddAssign {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn nan() -> Self;
+    fn from_f64(x: f64) -> Self;
+    fn from_usize(x: usize) -> Self;
+    fn to_f64(self) -> f64;
+    fn powf(self, p: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($t:ident, $powf:path, $fmin:path, $fmax:path, $fabs:path, $sqrt:path, $exp:path) => {
+        impl Float for $t {
+            fn zero() -> $t {
+                0.0
+            }
+
+            fn one() -> $t {
+                1.0
+            }
+
+            fn nan() -> $t {
+                $t::NAN
+            }
+
+            fn from_f64(x: f64) -> $t {
+                x as $t
+            }
+
+            fn from_usize(x: usize) -> $t {
+                x as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn powf(self, p: $t) -> $t {
+                $powf(self, p)
+            }
+
+            fn min(self, other: $t) -> $t {
+                $fmin(self, other)
+            }
+
+            fn max(self, other: $t) -> $t {
+                $fmax(self, other)
+            }
+
+            fn abs(self) -> $t {
+                $fabs(self)
+            }
+
+            fn sqrt(self) -> $t {
+                $sqrt(self)
+            }
+
+            fn exp(self) -> $t {
+                $exp(self)
+            }
+
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+impl_float!(f32, f32::powf, f32::min, f32::max, f32::abs, f32::sqrt, f32::exp);
+#[cfg(feature = "std")]
+impl_float!(f64, f64::powf, f64::min, f64::max, f64::abs, f64::sqrt, f64::exp);
+
+#[cfg(not(feature = "std"))]
+impl_float!(f32, ::libm::powf, ::libm::fminf, ::libm::fmaxf, ::libm::fabsf, ::libm::sqrtf, ::libm::expf);
+#[cfg(not(feature = "std"))]
+impl_float!(f64, ::libm::pow, ::libm::fmin, ::libm::fmax, ::libm::fabs, ::libm::sqrt, ::libm::exp);
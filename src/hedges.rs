@@ -6,65 +6,276 @@
 //
 // http://www.apache.org/licenses/LICENSE-2.0
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use alloc::sync::Arc;
+use core::fmt;
+use float::Float;
+use mf;
 
-static TYPES: [&'static str; 4] = ["very", "extremely", "somewhat", "slightly"];
+static TYPES: [&str; 10] = ["very", "extremely", "somewhat", "slightly", "not", "indeed", "more or less", "roughly", "above", "below"];
 
 pub fn types() -> Vec<&'static str> {
-    TYPES.iter().map(|&x| x).collect()
+    TYPES.to_vec()
 }
 
-pub fn compute(hedge: &Hedge, x: f32) -> f32 {
+// Number of words `name` takes if it is written at the start of `words`.
+fn matches(name: &str, words: &[&str]) -> Option<usize> {
+    let len = name.split(' ').count();
+    if words.len() >= len && name.split(' ').zip(words.iter()).all(|(a, b)| a == *b) {
+        Some(len)
+    }
+    else {
+        None
+    }
+}
+
+pub fn compute<T: Float>(hedge: &Hedge<T>, x: T) -> T {
     hedge.compute(x)
 }
 
+// `Above` and `Below` change the shape of a term: outside its core, on their side,
+// they give 1 - μ, and 0 elsewhere. The others only change the membership degree.
+// `Custom` hedges cannot be serialized.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum HedgeKind<T = f32> {
+    Power(T),
+    Not,
+    Indeed,
+    Above,
+    Below,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn HedgeFn<T>>)
+}
+
+impl<T: fmt::Debug> fmt::Debug for HedgeKind<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            HedgeKind::Power(ref p) => write!(f, "Power({:?})", p),
+            HedgeKind::Not => write!(f, "Not"),
+            HedgeKind::Indeed => write!(f, "Indeed"),
+            HedgeKind::Above => write!(f, "Above"),
+            HedgeKind::Below => write!(f, "Below"),
+            HedgeKind::Custom(_) => write!(f, "Custom")
+        }
+    }
+}
+
+// User defined hedges. `apply` also gets the term and the point it is evaluated at,
+// for hedges that change the shape of a term.
+pub trait HedgeFn<T>: Send + Sync {
+    fn compute(&self, x: T) -> T;
+
+    fn apply(&self, x: T, _mf: &mf::MfType<T>, _at: T) -> T {
+        self.compute(x)
+    }
+}
+
+impl<T, F: Fn(T) -> T + Send + Sync> HedgeFn<T> for F {
+    fn compute(&self, x: T) -> T {
+        self(x)
+    }
+}
+
+// Hedges known to the rule parser. Starts with the built-in ones; a hedge written
+// `name^p`, where `name` is a power hedge, raises to the power `p` instead.
 #[derive(Debug, Clone)]
-pub struct Hedge {
-    hedge: Option<Box<Hedge>>,
-    p: f32
+pub struct Registry<T = f32> {
+    hedges: Vec<(String, HedgeKind<T>)>
+}
+
+impl<T: Float> Default for Registry<T> {
+    fn default() -> Registry<T> {
+        Registry {
+            hedges: TYPES.iter().map(|name| (String::from(*name), Hedge::new(name, None).kind)).collect()
+        }
+    }
 }
 
-impl Hedge {
-    pub fn new(name: &str, hedge: Option<Box<Hedge>>) -> Hedge {
+impl<T: Float> Registry<T> {
+    pub fn new() -> Registry<T> {
+        Registry::default()
+    }
+
+    // Replaces any hedge of the same name.
+    pub fn register(&mut self, name: &str, kind: HedgeKind<T>) {
+        self.hedges.retain(|x| x.0 != name);
+        self.hedges.push((name.to_owned(), kind));
+    }
+
+    pub fn register_fn<F: HedgeFn<T> + 'static>(&mut self, name: &str, f: F) {
+        self.register(name, HedgeKind::Custom(Arc::new(f)));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HedgeKind<T>> {
+        self.hedges.iter().find(|x| x.0 == name).map(|x| &x.1)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.hedges.iter().map(|x| x.0.as_str()).collect()
+    }
+
+    // Hedge written at the start of `words`, the longest name first, with the number of words it takes.
+    pub fn parse(&self, words: &[&str]) -> Option<(Hedge<T>, usize)> {
+        if let Some(word) = words.first() {
+            if let Some(pos) = word.find('^') {
+                let p = word[pos + 1..].parse::<f64>().ok()?;
+                return match self.get(&word[..pos]) {
+                    Some(&HedgeKind::Power(_)) => Some((Hedge::init(word, None, HedgeKind::Power(T::from_f64(p))), 1)),
+                    _ => None
+                };
+            }
+        }
+        self.hedges.iter()
+            .filter_map(|x| matches(&x.0, words).map(|len| (x, len)))
+            .max_by_key(|&(_, len)| len)
+            .map(|(x, len)| (Hedge::init(&x.0, None, x.1.clone()), len))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hedge<T = f32> {
+    name: String,
+    hedge: Option<Box<Hedge<T>>>,
+    kind: HedgeKind<T>
+}
+
+// A chain applies `hedge` first, then `kind`. In rules the hedge written next to the
+// term is innermost, so "very not high" is very(not(high)).
+impl<T: Float> Hedge<T> {
+    pub fn new(name: &str, hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
         match name {
             "very" => Hedge::init_very(hedge),
             "extremely" => Hedge::init_extremely(hedge),
             "somewhat" => Hedge::init_somewhat(hedge),
             "slightly" => Hedge::init_slightly(hedge),
+            "not" => Hedge::init(name, hedge, HedgeKind::Not),
+            "indeed" => Hedge::init(name, hedge, HedgeKind::Indeed),
+            "more or less" => Hedge::init(name, hedge, HedgeKind::Power(T::from_f64(0.5))),
+            "roughly" => Hedge::init(name, hedge, HedgeKind::Power(T::from_f64(0.25))),
+            "above" => Hedge::init(name, hedge, HedgeKind::Above),
+            "below" => Hedge::init(name, hedge, HedgeKind::Below),
             _ => panic!("Hedge: '{}' does not exists!", name)
         }
     }
 
-    fn init(hedge: Option<Box<Hedge>>, p: f32) -> Hedge {
+    pub fn init(name: &str, hedge: Option<Box<Hedge<T>>>, kind: HedgeKind<T>) -> Hedge<T> {
         Hedge {
-            hedge: hedge,
-            p: p
+            name: name.to_owned(),
+            hedge,
+            kind
         }
     }
-   
-    fn init_very(hedge: Option<Box<Hedge>>) -> Hedge {
-        Hedge::init(hedge, 2f32)
+
+    fn init_very(hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
+        Hedge::init("very", hedge, HedgeKind::Power(T::from_f64(2.0)))
+    }
+
+    pub fn init_extremely(hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
+        Hedge::init("extremely", hedge, HedgeKind::Power(T::from_f64(3.0)))
     }
 
-    pub fn init_extremely(hedge: Option<Box<Hedge>>) -> Hedge {
-        Hedge::init(hedge, 3f32)
+    pub fn init_somewhat(hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
+        Hedge::init("somewhat", hedge, HedgeKind::Power(T::from_f64(0.5)))
     }
 
-    pub fn init_somewhat(hedge: Option<Box<Hedge>>) -> Hedge {
-        Hedge::init(hedge, 0.5f32)
+    pub fn init_slightly(hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
+        Hedge::init("slightly", hedge, HedgeKind::Power(T::from_f64(1.0 / 3.0)))
     }
 
-    pub fn init_slightly(hedge: Option<Box<Hedge>>) -> Hedge {
-        Hedge::init(hedge, 1f32 / 3f32)
+    pub fn name(&self) -> &str {
+        &self.name
     }
-    
-    pub fn compute(&self, x: f32) -> f32 {
-        let mut y = x;
+
+    pub fn kind(&self) -> &HedgeKind<T> {
+        &self.kind
+    }
+
+    // The same hedge applied on top of `hedge`.
+    pub fn chain(mut self, hedge: Option<Box<Hedge<T>>>) -> Hedge<T> {
+        self.hedge = hedge;
+        self
+    }
+
+    // Hedge names in the order they are written, outermost first.
+    pub fn words(&self) -> Vec<&str> {
+        let mut words = vec![self.name.as_str()];
         if let Some(ref hedge) = self.hedge {
-            if x > 0.0 {
-                y = hedge.compute(x);
+            words.extend(hedge.words());
+        }
+        words
+    }
+
+    // Kinds in the order they are applied, innermost first.
+    pub fn kinds(&self) -> Vec<HedgeKind<T>> {
+        let mut kinds = match self.hedge {
+            Some(ref hedge) => hedge.kinds(),
+            None => Vec::new()
+        };
+        kinds.push(self.kind.clone());
+        kinds
+    }
+
+    // Chained power hedges collapse into a single exponent; other chains have none.
+    pub fn exponent(&self) -> Option<T> {
+        let p = match self.kind {
+            HedgeKind::Power(p) => p,
+            _ => return None
+        };
+        match self.hedge {
+            Some(ref hedge) => hedge.exponent().map(|x| x * p),
+            None => Some(p)
+        }
+    }
+
+    // Membership degree `x` hedged without regard to the shape of the term, so
+    // `above` and `below` leave it unchanged.
+    pub fn compute(&self, x: T) -> T {
+        self.eval(x, None)
+    }
+
+    // Membership degree `x` of term `mf` at `at`, hedged.
+    pub fn apply(&self, x: T, mf: &mf::MfType<T>, at: T) -> T {
+        self.eval(x, Some((mf, at)))
+    }
+
+    fn eval(&self, x: T, shape: Option<(&mf::MfType<T>, T)>) -> T {
+        let y = match self.hedge {
+            Some(ref hedge) => hedge.eval(x, shape),
+            None => x
+        };
+        let half = T::from_f64(0.5);
+        let two = T::from_f64(2.0);
+        match self.kind {
+            HedgeKind::Power(p) => y.powf(p),
+            HedgeKind::Not => T::one() - y,
+            HedgeKind::Indeed if y <= half => two * y * y,
+            HedgeKind::Indeed => T::one() - two * (T::one() - y) * (T::one() - y),
+            HedgeKind::Above => match shape {
+                Some((mf, at)) => match mf.shape().core().1 {
+                    Some(end) if at > end => T::one() - y,
+                    _ => T::zero()
+                },
+                None => y
+            },
+            HedgeKind::Below => match shape {
+                Some((mf, at)) => match mf.shape().core().0 {
+                    Some(start) if at < start => T::one() - y,
+                    _ => T::zero()
+                },
+                None => y
+            },
+            HedgeKind::Custom(ref f) => match shape {
+                Some((mf, at)) => f.apply(y, mf, at),
+                None => f.compute(y)
             }
         }
-        y.powf(self.p)
     }
-    
 }
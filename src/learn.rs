@@ -0,0 +1,117 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use mf::MfType;
+use float::Float;
+use Engine;
+
+// Rule found by `wang_mendel`. `degree` is the product of the memberships of the
+// sample that produced it and `samples` the number of samples with the same antecedents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedRule<T = f32> {
+    pub antecedents: Vec<(String, String)>,
+    pub output: (String, String),
+    pub degree: T,
+    pub samples: usize
+}
+
+impl<T: Float> GeneratedRule<T> {
+    // In the syntax of `Engine::add_rules`.
+    pub fn to_rule(&self) -> String {
+        let antecedents: Vec<String> = self.antecedents.iter().map(|x| format!("{} is {}", x.0, x.1)).collect();
+        format!("if {} then {} is {}", antecedents.join(" and "), self.output.0, self.output.1)
+    }
+}
+
+// Index and membership of the term `x` belongs to most; the first one on ties.
+fn best<T: Float>(terms: &[MfType<T>], x: T) -> Option<(usize, T)> {
+    terms.iter().enumerate().fold(None, |best, (idx, mf)| {
+        let m = mf.compute(x);
+        match best {
+            Some((_, b)) if b >= m => best,
+            _ if m > T::zero() => Some((idx, m)),
+            _ => best
+        }
+    })
+}
+
+// Wang–Mendel method: each sample, given as the input values in `inputs` order and the
+// output value, gives a rule made of the term every value belongs to most, weighted by
+// the product of those memberships. Of the rules with the same antecedents, the one with
+// the highest degree is kept. Samples with a value in no term are skipped.
+pub fn wang_mendel<T: Float>(inputs: &[(&str, &[MfType<T>])], output: (&str, &[MfType<T>]), samples: &[(Vec<T>, T)]) -> Vec<GeneratedRule<T>> {
+    let mut rules: Vec<GeneratedRule<T>> = Vec::new();
+    let mut index: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+    'samples: for &(ref values, y) in samples {
+        let mut terms = Vec::with_capacity(inputs.len());
+        let mut degree = T::one();
+        for (&(_, vars), &x) in inputs.iter().zip(values.iter()) {
+            match best(vars, x) {
+                Some((term, m)) => {
+                    terms.push(term);
+                    degree = degree * m;
+                },
+                None => continue 'samples
+            }
+        }
+        if terms.len() != inputs.len() {
+            continue;
+        }
+        let (term, m) = match best(output.1, y) {
+            Some(x) => x,
+            None => continue
+        };
+        degree = degree * m;
+        let rule = GeneratedRule {
+            antecedents: terms.iter().zip(inputs.iter()).map(|(&term, &(name, vars))| (name.to_owned(), vars[term].name().to_owned())).collect(),
+            output: (output.0.to_owned(), output.1[term].name().to_owned()),
+            degree,
+            samples: 1
+        };
+        match index.get(&terms) {
+            Some(&idx) => {
+                let count = rules[idx].samples + 1;
+                if degree > rules[idx].degree {
+                    rules[idx] = rule;
+                }
+                rules[idx].samples = count;
+            },
+            None => {
+                index.insert(terms, rules.len());
+                rules.push(rule);
+            }
+        }
+    }
+    rules
+}
+
+impl<T: Float> Engine<T> {
+    // Rules for `output` from labelled samples, with inputs indexed as returned by `input_index`.
+    pub fn generate_rules(&self, output: &str, samples: &[(Vec<T>, T)]) -> Vec<GeneratedRule<T>> {
+        let var = match self.output.get(output) {
+            Some(var) => var,
+            None => panic!("Output field: '{}' not found", output)
+        };
+        let inputs: Vec<(&str, &[MfType<T>])> = self.input_names.iter()
+            .map(|name| (name.as_str(), &self.inputs[name].vars[..]))
+            .collect();
+        wang_mendel(&inputs, (output, &var.vars), samples)
+    }
+
+    pub fn add_generated_rules(&mut self, rules: &[GeneratedRule<T>]) {
+        for rule in rules {
+            if let Err(msg) = self.push_rule(&rule.to_rule()) {
+                panic!("{}", msg);
+            }
+        }
+    }
+}
@@ -6,57 +6,194 @@
 //
 // http://www.apache.org/licenses/LICENSE-2.0
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "log")]
 #[macro_use]
 extern crate log;
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+extern crate libm;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(all(not(feature = "std"), not(feature = "libm")))]
+compile_error!("rsfuzzy needs either the `std` or the `libm` feature");
 
-use std::vec::Vec;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::boxed::Box;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::fmt;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+use core::fmt;
 
+pub mod float;
 pub mod mf;
 pub mod deffuz;
 pub mod hedges;
-
-static OPERATOR: [&'static str; 3] = ["and", "or", "not"];
-
-use hedges::Hedge;
-use deffuz::{DefuzzType, Centroid, Mom, DefuzzOp};
-use std::f32;
-
-pub struct Engine {
-    inputs: HashMap<String, InputVar>,
-    output: HashMap<String, OutputVar>,
-    rules: Vec<(Vec<(String, mf::MfType, Option<Box<Hedge>>, Option<DefuzzOp>)>, Option<Box<Hedge>>, mf::MfType)>,
+pub mod config;
+pub mod builder;
+pub mod plan;
+pub mod batch;
+pub mod controller;
+pub mod fixed;
+pub mod lut;
+pub mod trace;
+pub mod explain;
+pub mod output;
+pub mod policy;
+pub mod coverage;
+pub mod lint;
+pub mod partition;
+pub mod learn;
+
+static OPERATOR: [&str; 3] = ["and", "or", "not"];
+
+use hedges::{Hedge, HedgeKind, HedgeFn, Registry};
+use deffuz::{DefuzzType, Centroid, Mom, DefuzzOp, Operators, Antecedent, Rule};
+use plan::{Plan, Scratch};
+use policy::Resolved;
+
+pub use float::Float;
+pub use config::ParseError;
+pub use builder::{EngineBuilder, BuiltEngine, BuildError};
+pub use batch::Layout;
+pub use fixed::{Q16, QEngine, FixedError};
+pub use lut::LookupTable;
+pub use trace::{Trace, VarTrace, AntecedentTrace, RuleTrace};
+pub use explain::Explainer;
+pub use output::FuzzyOutput;
+pub use policy::{NoFirePolicy, InferenceError, Outcome, RangePolicy, MissingPolicy, InputPolicy};
+pub use coverage::{Coverage, Gap};
+pub use lint::{Linter, Issue};
+pub use partition::{PartitionReport, Uniform};
+pub use learn::GeneratedRule;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de::Error as DeError;
+
+pub struct Engine<T = f32> {
+    inputs: BTreeMap<String, InputVar<T>>,
+    input_names: Vec<String>,
+    universes: Vec<(T, T)>,
+    policies: Vec<InputPolicy<T>>,
+    output: BTreeMap<String, OutputVar<T>>,
+    rules: Vec<Rule<T>>,
+    plan: Plan<T>,
+    sources: Vec<String>,
+    operators: Operators,
     range: (usize, usize),
     defuzz: DefuzzType,
+    no_fire: NoFirePolicy<T>,
+    hedges: Registry<T>,
+    // Every `add_hedge` call with the number of rules added before it, so serialization
+    // can replay them in order.
+    added_hedges: Vec<(usize, String, HedgeKind<T>)>,
+    // Last value returned by `calculate`, for `NoFirePolicy::Previous`.
+    #[cfg(feature = "std")]
+    previous: Mutex<Option<T>>,
 }
 
-impl fmt::Debug for Engine {
+impl<T: Float> fmt::Debug for Engine<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "Inputs: {:?}\nOutputs: {:?}\nRules: {:?}", self.inputs, self.output, self.rules)
     }
 }
 
 
-impl Engine {
-    pub fn new() -> Engine {
+impl<T: Float> Default for Engine<T> {
+    fn default() -> Engine<T> {
         Engine {
-            inputs: HashMap::with_capacity(3),
-            output: HashMap::with_capacity(1),
+            inputs: BTreeMap::new(),
+            input_names: Vec::with_capacity(3),
+            universes: Vec::with_capacity(3),
+            policies: Vec::with_capacity(3),
+            output: BTreeMap::new(),
             rules: Vec::new(),
+            plan: Plan::new(),
+            sources: Vec::new(),
+            operators: Operators::default(),
             range: (0, 0),
             defuzz: DefuzzType::Undefined,
+            no_fire: NoFirePolicy::Nan,
+            hedges: Registry::new(),
+            added_hedges: Vec::new(),
+            #[cfg(feature = "std")]
+            previous: Mutex::new(None),
         }
     }
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine::default()
+    }
+
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::new()
+    }
+
+    pub fn from_config(src: &str) -> Result<Engine, ParseError> {
+        config::parse(src)
+    }
+}
+
+// The constructors above always make `f32` engines, so float literals in the calling code
+// do not change the precision. These take it from the engine's type instead.
+impl<T: Float> Engine<T> {
+    pub fn with_float() -> Engine<T> {
+        Engine::default()
+    }
+
+    pub fn builder_with_float() -> EngineBuilder<T> {
+        EngineBuilder::default()
+    }
+
+    pub fn from_config_with_float(src: &str) -> Result<Engine<T>, ParseError> {
+        config::parse(src)
+    }
 
-    pub fn add_input_var(&mut self, name: &str, input_var: InputVar, start: usize, end: usize) {
+    pub fn add_input_var(&mut self, name: &str, input_var: InputVar<T>, start: usize, end: usize) {
         let mut i_var = input_var;
         i_var.start(start);
         i_var.end(end);
+        let universe = (T::from_usize(start), T::from_usize(end));
+        match self.input_index(name) {
+            Some(idx) => self.universes[idx] = universe,
+            None => {
+                self.input_names.push(name.to_owned());
+                self.universes.push(universe);
+                self.policies.push(InputPolicy::default());
+            }
+        }
         self.inputs.insert(name.to_owned(), i_var);
     }
 
-    pub fn add_output_var(&mut self, name: &str, output_var: OutputVar, start: usize, end: usize) {
+    pub fn add_output_var(&mut self, name: &str, output_var: OutputVar<T>, start: usize, end: usize) {
         let mut o_var = output_var;
         o_var.start(start);
         o_var.end(end);
@@ -75,129 +212,411 @@ impl Engine {
         self.defuzz = defuzz;
     }
 
-    pub fn add_rules(&mut self, rules: Vec<&str>) {
-        let hedges = hedges::types();
-        let operators: Vec<&'static str> = OPERATOR.iter().map(|&x| x).collect();
-        let mut idx;
-        let mut input_hedge: Option<Box<Hedge>>;
-        let mut output_hedge: Option<Box<Hedge>>;
-        let mut operator: Option<DefuzzOp>;
-        for rule in rules {
-            let fields: Vec<&str> = rule.trim().split(' ').collect();
-            idx = 0;
-            input_hedge = None;
-            output_hedge = None;
-            let mut input_vars: Vec<(String, mf::MfType, Option<Box<Hedge>>, Option<DefuzzOp>)> = Vec::new();
-            if fields[idx] != "if" {
-                panic!("Invalid syntax. 'if' missing in '{}'", rule);
-            }
-            idx += 1;
+    pub fn add_operator(&mut self, name: &str, kind: &str) {
+        if let Err(msg) = self.operators.set(name, kind) {
+            panic!("{}", msg);
+        }
+    }
 
-            loop {
-                let src_field;
-                let input_name = fields[idx];
-                if let Some(value) = self.inputs.get(input_name) {
-                    src_field = value;
-                }
-                else {
-                    panic!("Input field: '{}' not found", fields[idx]);
-                }
-                idx += 1;
-                if fields[idx] != "is" {
-                    panic!("Invalid syntax. 'is' missing in '{}'", rule);
-                }
-                idx += 1;
+    // Value returned when no rule fires for `name`, whatever the `NoFirePolicy`.
+    pub fn set_output_default(&mut self, name: &str, value: T) {
+        match self.output.get_mut(name) {
+            Some(var) => var.default = Some(value),
+            None => panic!("Output field: '{}' not found", name)
+        }
+    }
 
-                loop {
-                    if hedges.contains(&fields[idx]) {
-                        input_hedge = Some(Box::new(Hedge::new(fields[idx], input_hedge)));
-                        idx += 1;
-                    } else {
-                        break;
-                    }
-                }
-                let input_var;
-                if let Some(value) = src_field.get(fields[idx]) {
-                    input_var = value;
-                }
-                else {
-                    panic!("Invalid syntax. Input var '{}' not found", fields[idx]);
-                }
-                idx += 1;
-                if operators.contains(&fields[idx]) {
-                    operator = match fields[idx]{
-                        "and" => Some(DefuzzOp::And),
-                        "or" => Some(DefuzzOp::Or),
-                        "not" => Some(DefuzzOp::Not),
-                        _ => panic!("Operator '{}' not found!", fields[idx])
-                    };
-                    input_vars.push((input_name.to_owned(), input_var.clone(), input_hedge.clone(), operator));
-                    idx += 1;
-                }
-                else {
-                    input_vars.push((input_name.to_owned(), input_var.clone(), input_hedge.clone(), None));
-                    break;
-                }
+    pub fn set_input_policy(&mut self, name: &str, policy: InputPolicy<T>) {
+        match self.input_index(name) {
+            Some(idx) => self.policies[idx] = policy,
+            None => panic!("Input field: '{}' not found", name)
+        }
+    }
+
+    pub fn set_no_fire_policy(&mut self, policy: NoFirePolicy<T>) {
+        self.no_fire = policy;
+    }
 
+    // Hedges only apply to rules added after them.
+    pub fn add_hedge(&mut self, name: &str, kind: HedgeKind<T>) {
+        self.added_hedges.push((self.rules.len(), name.to_owned(), kind.clone()));
+        self.hedges.register(name, kind);
+    }
+
+    pub fn add_hedge_fn<F: HedgeFn<T> + 'static>(&mut self, name: &str, f: F) {
+        self.add_hedge(name, HedgeKind::Custom(Arc::new(f)));
+    }
+
+    pub fn hedges(&self) -> &Registry<T> {
+        &self.hedges
+    }
+
+    pub fn add_rules(&mut self, rules: Vec<&str>) {
+        for rule in rules {
+            if let Err(msg) = self.push_rule(rule) {
+                panic!("{}", msg);
             }
+        }
+    }
 
-            if fields[idx] != "then" {
-                panic!("Invalid syntax. 'then' missing in '{}'", rule);
+    fn push_rule(&mut self, rule: &str) -> Result<(), String> {
+        let operators: Vec<&'static str> = OPERATOR.to_vec();
+        let fields: Vec<&str> = rule.split_whitespace().collect();
+        let field = |idx: usize| fields.get(idx).cloned().unwrap_or("");
+        let mut idx = 0;
+        let mut operator: Option<DefuzzOp>;
+        let mut input_vars: Vec<Antecedent<T>> = Vec::new();
+        if field(idx) != "if" {
+            return Err(format!("Invalid syntax. 'if' missing in '{}'", rule));
+        }
+        idx += 1;
+
+        loop {
+            let input_name = field(idx);
+            let src_field = match self.inputs.get(input_name) {
+                Some(value) => value,
+                None => return Err(format!("Input field: '{}' not found", input_name))
+            };
+            idx += 1;
+            if field(idx) != "is" {
+                return Err(format!("Invalid syntax. 'is' missing in '{}'", rule));
             }
             idx += 1;
 
-            let dst_field;
-            if let Some(value) = self.output.get(fields[idx]) {
-                dst_field = value;
+            let input_hedge = self.parse_hedges(&fields, &mut idx, |name| src_field.get(name).is_some());
+            let input_var = match src_field.get(field(idx)) {
+                Some(value) => value,
+                None => return Err(format!("Invalid syntax. Input var '{}' not found", field(idx)))
+            };
+            idx += 1;
+            if operators.contains(&field(idx)) {
+                operator = match field(idx) {
+                    "and" => Some(DefuzzOp::And),
+                    "or" => Some(DefuzzOp::Or),
+                    "not" => Some(DefuzzOp::Not),
+                    _ => return Err(format!("Operator '{}' not found!", field(idx)))
+                };
+                input_vars.push((input_name.to_owned(), input_var.clone(), input_hedge.clone(), operator));
+                idx += 1;
             }
             else {
-                panic!("Output field: '{}' not found", fields[idx]);
+                input_vars.push((input_name.to_owned(), input_var.clone(), input_hedge.clone(), None));
+                break;
             }
-            debug!("dst: {:?}", dst_field);
-            idx += 1;
-            if fields[idx] != "is" {
-                panic!("Invalid syntax. 'is' missing in '{}'", rule);
-            }
-            idx += 1;
-            loop {
-                if hedges.contains(&fields[idx]) {
-                    output_hedge = Some(Box::new(Hedge::new(fields[idx], output_hedge)));
-                    idx += 1;
-                } else {
-                    break;
-                }
+        }
+
+        if field(idx) != "then" {
+            return Err(format!("Invalid syntax. 'then' missing in '{}'", rule));
+        }
+        idx += 1;
+
+        let destination = field(idx);
+        let dst_field = match self.output.get(destination) {
+            Some(value) => value,
+            None => return Err(format!("Output field: '{}' not found", destination))
+        };
+        debug!("dst: {:?}", dst_field);
+        idx += 1;
+        if field(idx) != "is" {
+            return Err(format!("Invalid syntax. 'is' missing in '{}'", rule));
+        }
+        idx += 1;
+        let output_hedge = self.parse_hedges(&fields, &mut idx, |name| dst_field.get(name).is_some());
+        let output_var = match dst_field.get(field(idx)) {
+            Some(value) => value,
+            None => return Err(format!("Invalid syntax. Output var '{}' not found", field(idx)))
+        };
+        self.range = dst_field.range();
+        let compiled = (input_vars, output_hedge, output_var.clone());
+        self.plan.set_range(self.range.0, self.range.1);
+        let input_names = &self.input_names;
+        self.plan.push(&compiled, destination, |name| input_names.iter().position(|x| x == name).unwrap());
+        self.rules.push(compiled);
+        self.sources.push(rule.trim().to_owned());
+        Ok(())
+    }
+
+    // Hedges from `fields[*idx]` on, chained so the last one written, next to the term, applies first.
+    // A term named like a hedge, such as `below`, is read as the term when no term follows it.
+    fn parse_hedges<F: Fn(&str) -> bool>(&self, fields: &[&str], idx: &mut usize, is_term: F) -> Option<Box<Hedge<T>>> {
+        let mut written = Vec::new();
+        let mut starts = Vec::new();
+        while let Some((hedge, len)) = self.hedges.parse(fields.get(*idx..).unwrap_or(&[])) {
+            written.push(hedge);
+            starts.push(*idx);
+            *idx += len;
+        }
+        if !is_term(fields.get(*idx).cloned().unwrap_or("")) {
+            if let Some(last) = (0..starts.len()).rev().find(|&n| is_term(fields[starts[n]])) {
+                *idx = starts[last];
+                written.truncate(last);
             }
-            let output_var;
-            if let Some(value) = dst_field.get(fields[idx]) {
-                output_var = value;
+        }
+        written.into_iter().rev().fold(None, |inner, hedge| Some(Box::new(hedge.chain(inner))))
+    }
+
+    pub fn fuzzify(&self, name: &str, x: T) -> Option<Vec<(&str, T)>> {
+        self.inputs.get(name).map(|var| var.fuzzify(x))
+    }
+
+    pub fn fuzzify_with(&self, name: &str, x: T, hedge: &Hedge<T>) -> Option<Vec<(&str, T)>> {
+        self.inputs.get(name).map(|var| var.fuzzify_with(x, hedge))
+    }
+
+    pub fn output_var(&self, name: &str) -> Option<&OutputVar<T>> {
+        self.output.get(name)
+    }
+
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        self.input_names.iter().position(|x| x == name)
+    }
+
+    pub fn input_count(&self) -> usize {
+        self.input_names.len()
+    }
+
+    pub fn scratch(&self) -> Scratch<T> {
+        self.plan.scratch()
+    }
+
+    // `NoFirePolicy::Previous` falls back to the last value `calculate` returned.
+    #[cfg(feature = "std")]
+    pub fn calculate(&self, inputs: HashMap<String, T>) -> T {
+        let values: Vec<T> = self.input_names.iter().map(|name| match inputs.get(name) {
+            Some(val) => *val,
+            None => T::nan()
+        }).collect();
+        let mut scratch = self.scratch();
+        if let Some(x) = *self.previous.lock().unwrap() {
+            scratch.set_previous(x);
+        }
+        let result = self.evaluate(&values, &mut scratch);
+        *self.previous.lock().unwrap() = scratch.previous();
+        match result {
+            Ok(outcome) => outcome.value,
+            Err(InferenceError::MissingInput(name)) => panic!("Variable {} not defined.", name),
+            Err(_) => T::nan()
+        }
+    }
+
+    // Inputs are indexed as returned by `input_index`. Gives NaN where `evaluate` fails.
+    pub fn calculate_into(&self, inputs: &[T], scratch: &mut Scratch<T>) -> T {
+        match self.evaluate(inputs, scratch) {
+            Ok(outcome) => outcome.value,
+            Err(_) => T::nan()
+        }
+    }
+
+    fn resolve(&self, idx: usize, x: T) -> Result<Resolved<T>, InferenceError> {
+        let policy = &self.policies[idx];
+        let x = if x.is_nan() {
+            match policy.missing {
+                MissingPolicy::Error => return Err(InferenceError::MissingInput(self.input_names[idx].clone())),
+                MissingPolicy::Default(x) => x,
+                MissingPolicy::Unknown(degree) => return Ok(Resolved::Degree(degree))
             }
-            else {
-                panic!("Invalid syntax. Output var '{}' not found", fields[idx]);
+        }
+        else {
+            x
+        };
+        let (start, end) = self.universes[idx];
+        match policy.range {
+            RangePolicy::Extrapolate => Ok(Resolved::Value(x)),
+            RangePolicy::Clamp => Ok(Resolved::Value(x.max(start).min(end))),
+            RangePolicy::Reject if x < start || x > end => Err(InferenceError::OutOfRange(self.input_names[idx].clone())),
+            RangePolicy::Reject => Ok(Resolved::Value(x))
+        }
+    }
+
+    // Value of input `idx`; inputs past the end of the slice are missing, like NaN.
+    fn input_value(&self, inputs: &[T], idx: usize) -> T {
+        inputs.get(idx).cloned().unwrap_or_else(T::nan)
+    }
+
+    // Applies the input policies and fills `scratch` with the antecedent memberships.
+    fn fuzzify_into(&self, inputs: &[T], scratch: &mut Scratch<T>) -> Result<(), InferenceError> {
+        for idx in 0..self.input_names.len() {
+            if self.plan.uses(idx) {
+                self.resolve(idx, self.input_value(inputs, idx))?;
             }
-            self.range = dst_field.range();
-            self.rules.push((input_vars.clone(), output_hedge.clone(), output_var.clone()));
         }
+        self.plan.fuzzify_with(scratch, |idx| {
+            let x = self.input_value(inputs, idx);
+            self.resolve(idx, x).unwrap_or(Resolved::Value(x))
+        });
+        Ok(())
     }
 
-    pub fn calculate(&self, inputs: HashMap<String, f32>) -> f32 {
-        let (start, end) = self.range;
+    pub fn evaluate(&self, inputs: &[T], scratch: &mut Scratch<T>) -> Result<Outcome<T>, InferenceError> {
         if let DefuzzType::Undefined = self.defuzz {
-            return f32::NAN;
+            return Err(InferenceError::UndefinedDefuzz);
+        }
+        self.fuzzify_into(inputs, scratch)?;
+        let fired_rules = self.plan.fired(scratch, &self.operators);
+        let mut value = self.defuzz.eval(&self.plan, scratch, &self.operators);
+        if fired_rules == 0 || value.is_nan() {
+            value = match (self.output.get(self.plan.destination()).and_then(|var| var.default), self.no_fire) {
+                (Some(x), _) => x,
+                (None, NoFirePolicy::Nan) => value,
+                (None, NoFirePolicy::Error) => return Err(InferenceError::NoRuleFired),
+                (None, NoFirePolicy::Default(x)) => x,
+                (None, NoFirePolicy::Previous) => match scratch.previous() {
+                    Some(x) => x,
+                    None => return Err(InferenceError::NoRuleFired)
+                }
+            };
+        }
+        if !value.is_nan() {
+            scratch.set_previous(value);
         }
-        self.defuzz.get(start, end, &self.rules, inputs)
+        Ok(Outcome {
+            value,
+            fired_rules
+        })
     }
 
+    // Maximum of the implied rule outputs, for memberships left by `fuzzify`.
+    fn aggregate(&self, scratch: &Scratch<T>) -> Vec<T> {
+        let mut samples = vec![T::zero(); self.plan.sample_count()];
+        for rule in 0..self.plan.rule_count() {
+            let strength = self.plan.strength(rule, scratch, &self.operators);
+            for (idx, total) in samples.iter_mut().enumerate() {
+                *total = total.max(self.plan.imply(rule, idx, strength));
+            }
+        }
+        samples
+    }
+
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct EngineRef<'a, T: 'a> {
+    inputs: &'a BTreeMap<String, InputVar<T>>,
+    input_names: &'a [String],
+    outputs: &'a BTreeMap<String, OutputVar<T>>,
+    rules: &'a [String],
+    operators: &'a Operators,
+    defuzz: &'a DefuzzType,
+    no_fire: &'a NoFirePolicy<T>,
+    policies: BTreeMap<&'a str, &'a InputPolicy<T>>,
+    hedges: Vec<HedgeRef<'a, T>>,
+}
+
+// A hedge added after `after_rules` rules, so it only applies to the rules that follow.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct HedgeRef<'a, T: 'a> {
+    name: &'a str,
+    kind: &'a HedgeKind<T>,
+    after_rules: usize
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HedgeDef<T> {
+    name: String,
+    kind: HedgeKind<T>,
+    #[serde(default)]
+    after_rules: usize
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct EngineDef<T> {
+    inputs: BTreeMap<String, InputVar<T>>,
+    // Order of `input_index`; inputs missing from it follow by name.
+    #[serde(default)]
+    input_names: Vec<String>,
+    outputs: BTreeMap<String, OutputVar<T>>,
+    rules: Vec<String>,
+    #[serde(default)]
+    operators: Operators,
+    defuzz: DefuzzType,
+    #[serde(default)]
+    no_fire: NoFirePolicy<T>,
+    #[serde(default)]
+    policies: BTreeMap<String, InputPolicy<T>>,
+    #[serde(default)]
+    hedges: Vec<HedgeDef<T>>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Float + Serialize> Serialize for Engine<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EngineRef {
+            inputs: &self.inputs,
+            input_names: &self.input_names,
+            outputs: &self.output,
+            rules: &self.sources,
+            operators: &self.operators,
+            defuzz: &self.defuzz,
+            no_fire: &self.no_fire,
+            policies: self.input_names.iter().map(|x| x.as_str()).zip(self.policies.iter()).collect(),
+            hedges: self.added_hedges.iter().map(|x| HedgeRef {
+                name: &x.1,
+                kind: &x.2,
+                after_rules: x.0
+            }).collect(),
+        }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Float + Deserialize<'de>> Deserialize<'de> for Engine<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Engine<T>, D::Error> {
+        let mut def = EngineDef::deserialize(deserializer)?;
+        let mut engine = Engine::default();
+        for name in &def.input_names {
+            let var = match def.inputs.remove(name) {
+                Some(var) => var,
+                None => return Err(D::Error::custom(format!("Input field: '{}' not found", name)))
+            };
+            let (start, end) = (var.start, var.end);
+            engine.add_input_var(name, var, start, end);
+        }
+        for (name, var) in def.inputs {
+            let (start, end) = (var.start, var.end);
+            engine.add_input_var(&name, var, start, end);
+        }
+        for (name, var) in def.outputs {
+            let (start, end) = var.range();
+            engine.add_output_var(&name, var, start, end);
+        }
+        for (name, policy) in def.policies {
+            match engine.input_index(&name) {
+                Some(idx) => engine.policies[idx] = policy,
+                None => return Err(D::Error::custom(format!("Input field: '{}' not found", name)))
+            }
+        }
+        engine.operators = def.operators;
+        def.hedges.sort_by_key(|x| x.after_rules);
+        let mut hedges = def.hedges.into_iter().peekable();
+        for (idx, rule) in def.rules.iter().enumerate() {
+            while let Some(hedge) = hedges.next_if(|x| x.after_rules <= idx) {
+                engine.add_hedge(&hedge.name, hedge.kind);
+            }
+            engine.push_rule(rule).map_err(D::Error::custom)?;
+        }
+        for hedge in hedges {
+            engine.add_hedge(&hedge.name, hedge.kind);
+        }
+        engine.defuzz = def.defuzz;
+        engine.no_fire = def.no_fire;
+        Ok(engine)
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct InputVar {
-    vars: Vec<mf::MfType>,
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputVar<T = f32> {
+    vars: Vec<mf::MfType<T>>,
     start: usize,
     end: usize
 }
 
-impl InputVar {
-    pub fn new(input: Vec<mf::MfType>) -> InputVar{
+impl<T: Float> InputVar<T> {
+    pub fn new(input: Vec<mf::MfType<T>>) -> InputVar<T> {
         InputVar {
             vars: input,
             start: 0,
@@ -213,30 +632,36 @@ impl InputVar {
         self.end = end;
     }
 
-    fn get(&self, name: &str) -> Option<&mf::MfType>{
-        for var in &self.vars {
-            if var.name() == name {
-                return Some(var);
-            }
-        }
-        None
+    fn get(&self, name: &str) -> Option<&mf::MfType<T>> {
+        self.vars.iter().find(|var| var.name() == name)
     }
 
+    pub fn fuzzify(&self, x: T) -> Vec<(&str, T)> {
+        self.vars.iter().map(|var| (var.name(), var.compute(x))).collect()
+    }
+
+    pub fn fuzzify_with(&self, x: T, hedge: &Hedge<T>) -> Vec<(&str, T)> {
+        self.vars.iter().map(|var| (var.name(), hedge.apply(var.compute(x), var, x))).collect()
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct OutputVar {
-    vars: Vec<mf::MfType>,
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutputVar<T = f32> {
+    vars: Vec<mf::MfType<T>>,
     start: usize,
-    end: usize
+    end: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    default: Option<T>
 }
 
-impl OutputVar {
-    pub fn new(input: Vec<mf::MfType>) -> OutputVar{
+impl<T: Float> OutputVar<T> {
+    pub fn new(input: Vec<mf::MfType<T>>) -> OutputVar<T> {
         OutputVar {
             vars: input,
             start: 0,
-            end: 0
+            end: 0,
+            default: None
         }
     }
 
@@ -248,17 +673,84 @@ impl OutputVar {
         self.end = end;
     }
 
-    fn get(&self, name: &str) -> Option<&mf::MfType>{
+    fn get(&self, name: &str) -> Option<&mf::MfType<T>> {
+        self.vars.iter().find(|var| var.name() == name)
+    }
+
+    fn range(&self) -> (usize, usize) {
+        (self.start, self.end)
+    }
+
+    // Labels are a term, optionally preceded by one of the engine's power hedges,
+    // each with its set sampled at `start`, `start + 1`, ... Hedges that change the
+    // shape of a term or negate it, such as `not` or `above`, are left out.
+    fn labels(&self, engine: &Engine<T>) -> Vec<(String, Vec<T>)> {
+        let hedges: Vec<Hedge<T>> = engine.hedges.names().into_iter().filter_map(|name| match engine.hedges.get(name) {
+            Some(&HedgeKind::Power(p)) => Some(Hedge::init(name, None, HedgeKind::Power(p))),
+            _ => None
+        }).collect();
+        let mut labels = Vec::new();
         for var in &self.vars {
-            if var.name() == name {
-                return Some(var);
+            let set: Vec<T> = (self.start..self.end).map(|y| var.compute(T::from_usize(y))).collect();
+            let hedged: Vec<(String, Vec<T>)> = hedges.iter().map(|hedge| {
+                let hedged = set.iter().map(|&m| hedge.compute(m));
+                (format!("{} {}", hedge.name(), var.name()), hedged.collect())
+            }).collect();
+            labels.push((var.name().to_owned(), set));
+            labels.extend(hedged);
+        }
+        labels
+    }
+
+    fn best<F: Fn(&[T]) -> Option<T>>(&self, engine: &Engine<T>, score: F) -> Option<(String, T)> {
+        let mut best: Option<(String, T)> = None;
+        for (label, set) in self.labels(engine) {
+            let score = match score(&set) {
+                Some(score) => score,
+                None => continue
+            };
+            match best {
+                Some(ref best) if score <= best.1 => (),
+                _ => best = Some((label, score))
             }
         }
-        None
+        best
     }
 
-    fn range(&self) -> (usize, usize) {
-        (self.start, self.end)
+    // Best label for a set sampled at `start`, `start + 1`, ..., scored by Jaccard
+    // similarity. Sets higher than 1 are scaled down first.
+    // `engine` gives the hedges to try.
+    pub fn approximate(&self, engine: &Engine<T>, set: &[T]) -> Option<(String, T)> {
+        let height = set.iter().fold(T::zero(), |a, &b| a.max(b));
+        let scale = if height > T::one() { height } else { T::one() };
+        self.best(engine, |m: &[T]| {
+            let mut common = T::zero();
+            let mut total = T::zero();
+            for (&m, &x) in m.iter().zip(set.iter()) {
+                common += m.min(x / scale);
+                total += m.max(x / scale);
+            }
+            Some(if total > T::zero() { common / total } else { T::zero() })
+        })
+    }
+
+    // Best label for a crisp value: the one whose centroid is nearest, scored as
+    // 1 - distance / width of the universe.
+    pub fn approximate_value(&self, engine: &Engine<T>, x: T) -> Option<(String, T)> {
+        let width = T::from_usize(self.end.saturating_sub(self.start).max(1));
+        let start = self.start;
+        self.best(engine, |m: &[T]| {
+            let mut first = T::zero();
+            let mut second = T::zero();
+            for (idx, &m) in m.iter().enumerate() {
+                first += T::from_usize(start + idx) * m;
+                second += m;
+            }
+            if second <= T::zero() {
+                return None;
+            }
+            Some(T::one() - (first / second - x).abs() / width)
+        })
     }
 }
 
@@ -267,13 +759,14 @@ impl OutputVar {
 macro_rules! fz_input_var {
     ( $( $x:expr ),* ) => {
         {
-            let mut vars: Vec<$crate::mf::MfType> = Vec::new();
+            let mut vars: Vec<$crate::mf::MfType<_>> = Vec::new();
             $(
                 let value = match $x.0 {
                     "triangle" => $crate::mf::Triangle::new($x.1, $x.2),
                     "trapezoid" => $crate::mf::Trapezoid::new($x.1, $x.2),
                     "up" => $crate::mf::Up::new($x.1, $x.2),
                     "down" => $crate::mf::Down::new($x.1, $x.2),
+                    "gaussian" => $crate::mf::Gaussian::new($x.1, $x.2),
                     _ => panic!("No MF found for type: {}", $x.0)
 
                 };
@@ -289,13 +782,14 @@ macro_rules! fz_input_var {
 macro_rules! fz_output_var {
     ( $( $x:expr ),* ) => {
         {
-            let mut vars: Vec<$crate::mf::MfType> = Vec::new();
+            let mut vars: Vec<$crate::mf::MfType<_>> = Vec::new();
             $(
                 let value = match $x.0 {
                     "triangle" => $crate::mf::Triangle::new($x.1, $x.2),
                     "trapezoid" => $crate::mf::Trapezoid::new($x.1, $x.2),
                     "up" => $crate::mf::Up::new($x.1, $x.2),
                     "down" => $crate::mf::Down::new($x.1, $x.2),
+                    "gaussian" => $crate::mf::Gaussian::new($x.1, $x.2),
                     _ => panic!("No MF found for type: {}", $x.0)
                 };
                vars.push(value);
@@ -310,7 +804,7 @@ macro_rules! fz_output_var {
 macro_rules! fz_set_inputs {
     ( $( $x:expr ),* ) => {
         {
-            let mut inputs: HashMap<String, f32> = HashMap::new();
+            let mut inputs = HashMap::new();
             $(
                 let name = $x.0;
                 let val = $x.1;
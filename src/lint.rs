@@ -0,0 +1,225 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use deffuz::{DefuzzOp, Antecedent};
+use hedges::{Hedge, HedgeKind};
+use mf::Shape;
+use float::Float;
+use Engine;
+
+// Rules are numbered by their position in `Engine::rules`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue<T = f32> {
+    // Same antecedents, consequents whose centroids are `distance` apart, as a fraction of the output universe.
+    Conflict { rule: usize, with: usize, distance: T },
+    Duplicate { rule: usize, of: usize },
+    // Every antecedent of `by` is in `rule`, with the same consequent. `rule` only fires
+    // where `by` does, so the centroid, which sums activations, weighs that consequent twice there.
+    Subsumed { rule: usize, by: usize },
+    NeverFires(usize),
+    UnusedTerm { var: String, term: String },
+    UnusedVar(String)
+}
+
+impl<T: Float> fmt::Display for Issue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Issue::Conflict { rule, with, distance } => write!(f, "Rule {} conflicts with rule {} (distance {:.2})", rule, with, distance.to_f64()),
+            Issue::Duplicate { rule, of } => write!(f, "Rule {} duplicates rule {}", rule, of),
+            Issue::Subsumed { rule, by } => write!(f, "Rule {} is subsumed by rule {}", rule, by),
+            Issue::NeverFires(rule) => write!(f, "Rule {} can never fire", rule),
+            Issue::UnusedTerm { ref var, ref term } => write!(f, "Term '{}' of '{}' is not used by any rule", term, var),
+            Issue::UnusedVar(ref var) => write!(f, "Variable '{}' is not used by any rule", var)
+        }
+    }
+}
+
+// Rules with the same antecedents conflict when their consequents are at least
+// `conflict_distance` of the output universe apart. Defaults to 0.25.
+#[derive(Debug, Clone, Copy)]
+pub struct Linter<T = f32> {
+    conflict_distance: T
+}
+
+impl<T: Float> Default for Linter<T> {
+    fn default() -> Linter<T> {
+        Linter {
+            conflict_distance: T::from_f64(0.25)
+        }
+    }
+}
+
+// Antecedents as (input, term, hedges), or `None` if some are not joined by `and`.
+type Conjunction<'a> = Option<Vec<(&'a str, &'a str, Vec<&'a str>)>>;
+
+fn conjunction<T: Float>(vars: &[Antecedent<T>]) -> Conjunction<'_> {
+    let last = vars.len().saturating_sub(1);
+    if vars[..last].iter().any(|var| var.3 != Some(DefuzzOp::And)) {
+        return None;
+    }
+    Some(vars.iter().map(|var| (var.0.as_str(), var.1.name(), hedge_words(&var.2))).collect())
+}
+
+// Antecedents as written, with the operator that follows each.
+fn antecedents<T: Float>(vars: &[Antecedent<T>]) -> Vec<(&str, &str, Vec<&str>, Option<DefuzzOp>)> {
+    vars.iter().map(|var| (var.0.as_str(), var.1.name(), hedge_words(&var.2), var.3)).collect()
+}
+
+fn hedge_words<T: Float>(hedge: &Option<Box<Hedge<T>>>) -> Vec<&str> {
+    match *hedge {
+        Some(ref hedge) => hedge.words(),
+        None => Vec::new()
+    }
+}
+
+// Support of `shape`, unbounded on the shoulders' open sides.
+fn support<T: Float>(shape: Shape<T>) -> (T, T) {
+    let (a, b) = shape.support();
+    (a.unwrap_or_else(|| T::from_f64(f64::NEG_INFINITY)), b.unwrap_or_else(|| T::from_f64(f64::INFINITY)))
+}
+
+impl<T: Float> Linter<T> {
+    pub fn new() -> Linter<T> {
+        Linter::default()
+    }
+
+    pub fn conflict_distance(mut self, distance: T) -> Linter<T> {
+        self.conflict_distance = distance;
+        self
+    }
+
+    pub fn lint(&self, engine: &Engine<T>) -> Vec<Issue<T>> {
+        let mut issues = Vec::new();
+        let rules = &engine.rules;
+        let outputs: Vec<&str> = (0..rules.len()).map(|rule| engine.plan.output(rule)).collect();
+        let conjunctions: Vec<Conjunction> = rules.iter().map(|rule| conjunction(&rule.0)).collect();
+        let centroids: Vec<Option<T>> = (0..rules.len()).map(|rule| self.centroid(engine, rule)).collect();
+        let (start, end) = engine.plan.range();
+        let width = T::from_usize(end.saturating_sub(start).max(1));
+
+        for rule in 0..rules.len() {
+            for other in 0..rule {
+                let same_output = outputs[rule] == outputs[other];
+                let same_consequent = same_output && rules[rule].2.name() == rules[other].2.name() &&
+                    hedge_words(&rules[rule].1) == hedge_words(&rules[other].1);
+                let same_antecedents = match (&conjunctions[rule], &conjunctions[other]) {
+                    (Some(a), Some(b)) => a.len() == b.len() && a.iter().all(|x| b.contains(x)),
+                    _ => antecedents(&rules[rule].0) == antecedents(&rules[other].0)
+                };
+                if same_antecedents && same_consequent {
+                    issues.push(Issue::Duplicate { rule, of: other });
+                }
+                else if same_antecedents && same_output {
+                    if let (Some(a), Some(b)) = (centroids[rule], centroids[other]) {
+                        let distance = (a - b).abs() / width;
+                        if distance >= self.conflict_distance {
+                            issues.push(Issue::Conflict { rule, with: other, distance });
+                        }
+                    }
+                }
+            }
+        }
+
+        for rule in 0..rules.len() {
+            for other in 0..rules.len() {
+                let same_consequent = outputs[rule] == outputs[other] && rules[rule].2.name() == rules[other].2.name() &&
+                    hedge_words(&rules[rule].1) == hedge_words(&rules[other].1);
+                if let (true, Some(a), Some(b)) = (same_consequent, &conjunctions[rule], &conjunctions[other]) {
+                    if b.len() < a.len() && b.iter().all(|x| a.contains(x)) {
+                        issues.push(Issue::Subsumed { rule, by: other });
+                    }
+                }
+            }
+        }
+
+        for (rule, vars) in rules.iter().enumerate() {
+            if self.never_fires(engine, &vars.0) {
+                issues.push(Issue::NeverFires(rule));
+            }
+        }
+
+        for name in &engine.input_names {
+            let var = &engine.inputs[name];
+            let used: Vec<&str> = rules.iter().flat_map(|rule| rule.0.iter())
+                .filter(|ant| ant.0 == *name)
+                .map(|ant| ant.1.name())
+                .collect();
+            if used.is_empty() {
+                issues.push(Issue::UnusedVar(name.clone()));
+                continue;
+            }
+            for mf in var.vars.iter().filter(|mf| !used.contains(&mf.name())) {
+                issues.push(Issue::UnusedTerm { var: name.clone(), term: mf.name().to_owned() });
+            }
+        }
+        for (name, var) in &engine.output {
+            let used: Vec<&str> = rules.iter().zip(outputs.iter())
+                .filter(|&(_, output)| output == name)
+                .map(|(rule, _)| rule.2.name())
+                .collect();
+            if used.is_empty() {
+                issues.push(Issue::UnusedVar(name.clone()));
+                continue;
+            }
+            for mf in var.vars.iter().filter(|mf| !used.contains(&mf.name())) {
+                issues.push(Issue::UnusedTerm { var: name.clone(), term: mf.name().to_owned() });
+            }
+        }
+        issues
+    }
+
+    // Centroid of the hedged consequent of `rule`.
+    fn centroid(&self, engine: &Engine<T>, rule: usize) -> Option<T> {
+        let (start, _) = engine.plan.range();
+        let mut first = T::zero();
+        let mut second = T::zero();
+        for idx in 0..engine.plan.sample_count() {
+            let m = engine.plan.sample(rule, idx);
+            first += T::from_usize(start + idx) * m;
+            second += m;
+        }
+        if second > T::zero() { Some(first / second) } else { None }
+    }
+
+    // Only rules made of `and` are checked: they cannot fire when two antecedents on
+    // the same input have disjoint supports, or one lies outside its input's universe.
+    // Antecedents with hedges other than powers are skipped, as those change the support.
+    fn never_fires(&self, engine: &Engine<T>, vars: &[Antecedent<T>]) -> bool {
+        if conjunction(vars).is_none() {
+            return false;
+        }
+        let supports: Vec<(&str, (T, T))> = vars.iter()
+            .filter(|var| match var.2 {
+                Some(ref hedge) => hedge.kinds().iter().all(|x| matches!(*x, HedgeKind::Power(_))),
+                None => true
+            })
+            .map(|var| (var.0.as_str(), support(var.1.shape())))
+            .collect();
+        supports.iter().enumerate().any(|(idx, &(name, (a, b)))| {
+            let outside = match engine.input_index(name) {
+                Some(input) => {
+                    let (start, end) = engine.universes[input];
+                    b <= start || a >= end
+                },
+                None => false
+            };
+            outside || supports[..idx].iter().any(|&(other, (c, d))| other == name && (b <= c || d <= a))
+        })
+    }
+}
+
+impl<T: Float> Engine<T> {
+    pub fn lint(&self) -> Vec<Issue<T>> {
+        Linter::default().lint(self)
+    }
+}
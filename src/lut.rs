@@ -0,0 +1,116 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use alloc::vec::Vec;
+use float::Float;
+use Engine;
+
+// Control surface sampled on a regular grid over the input universes. Values are
+// stored row-major with the last input varying fastest.
+#[derive(Debug, Clone)]
+pub struct LookupTable<T = f32> {
+    ranges: Vec<(T, T)>,
+    resolution: usize,
+    values: Vec<T>,
+    max_error: T
+}
+
+impl<T: Float> LookupTable<T> {
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.ranges.len()
+    }
+
+    // Largest difference to the exact engine, measured at the centre of every cell.
+    // Infinite when either side is NaN there, such as where no rule fires.
+    pub fn max_error(&self) -> T {
+        self.max_error
+    }
+
+    // Inputs are indexed as returned by `Engine::input_index` and clamped to their universes.
+    pub fn calculate(&self, inputs: &[T]) -> T {
+        let dims = self.ranges.len();
+        let mut result = T::zero();
+        for corner in 0..(1usize << dims) {
+            let mut weight = T::one();
+            let mut offset = 0;
+            for (dim, &(start, end)) in self.ranges.iter().enumerate() {
+                let (idx, frac) = self.cell(inputs[dim], start, end);
+                let upper = (corner >> (dims - 1 - dim)) & 1 == 1;
+                offset = offset * self.resolution + idx + upper as usize;
+                weight = weight * if upper { frac } else { T::one() - frac };
+            }
+            if weight > T::zero() {
+                result += weight * self.values[offset];
+            }
+        }
+        result
+    }
+
+    fn cell(&self, x: T, start: T, end: T) -> (usize, T) {
+        let steps = T::from_usize(self.resolution - 1);
+        let t = ((x - start) / (end - start) * steps).max(T::zero()).min(steps);
+        let idx = (t.to_f64() as usize).min(self.resolution - 2);
+        (idx, t - T::from_usize(idx))
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // Samples `resolution` points per input, so the table holds resolution^inputs values.
+    pub fn compile_lut(&self, resolution: usize) -> LookupTable<T> {
+        if resolution < 2 {
+            panic!("Lookup table resolution must be at least 2");
+        }
+        let ranges: Vec<(T, T)> = self.input_names.iter().map(|name| {
+            let var = &self.inputs[name];
+            (T::from_usize(var.start), T::from_usize(var.end))
+        }).collect();
+        let dims = ranges.len();
+        let mut table = LookupTable {
+            ranges,
+            resolution,
+            values: Vec::new(),
+            max_error: T::zero()
+        };
+
+        let mut scratch = self.scratch();
+        let mut point = vec![T::zero(); dims];
+        let step = |x: usize, (start, end): (T, T), n: usize| {
+            start + (end - start) * T::from_usize(x) / T::from_usize(n)
+        };
+        let size = resolution.pow(dims as u32);
+        table.values = (0..size).map(|idx| {
+            let mut rest = idx;
+            for dim in (0..dims).rev() {
+                point[dim] = step(rest % resolution, table.ranges[dim], resolution - 1);
+                rest /= resolution;
+            }
+            self.calculate_into(&point, &mut scratch)
+        }).collect();
+
+        let cells = (resolution - 1).pow(dims as u32);
+        for idx in 0..cells {
+            let mut rest = idx;
+            for dim in (0..dims).rev() {
+                point[dim] = step(2 * (rest % (resolution - 1)) + 1, table.ranges[dim], 2 * (resolution - 1));
+                rest /= resolution - 1;
+            }
+            let error = (table.calculate(&point) - self.calculate_into(&point, &mut scratch)).abs();
+            table.max_error = if error.is_nan() {
+                T::from_f64(f64::INFINITY)
+            }
+            else {
+                table.max_error.max(error)
+            };
+        }
+        table
+    }
+}
@@ -6,46 +6,116 @@
 //
 // http://www.apache.org/licenses/LICENSE-2.0
 
+#![allow(clippy::new_ret_no_self)]
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use float::Float;
+
 #[derive(Debug, Clone)]
-pub enum MfType {
-    Triangle(Triangle),
-    Trapezoid(Trapezoid),
-    Up(Up),
-    Down(Down)
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "lowercase"))]
+pub enum MfType<T = f32> {
+    Triangle(Triangle<T>),
+    Trapezoid(Trapezoid<T>),
+    Up(Up<T>),
+    Down(Down<T>),
+    Gaussian(Gaussian<T>)
 }
 
-impl MfType {
-    pub fn compute(&self, x: f32) -> f32 {
+impl<T: Float> MfType<T> {
+    pub fn compute(&self, x: T) -> T {
         match *self {
             MfType::Triangle(ref value) => value.compute(x),
             MfType::Trapezoid(ref value) => value.compute(x),
             MfType::Up(ref value) => value.compute(x),
-            MfType::Down(ref value) => value.compute(x)
+            MfType::Down(ref value) => value.compute(x),
+            MfType::Gaussian(ref value) => value.compute(x)
         }
     }
-    
+
     pub fn name(&self) -> &str {
         match *self {
             MfType::Triangle(ref value) => value.name(),
             MfType::Trapezoid(ref value) => value.name(),
             MfType::Up(ref value) => value.name(),
-            MfType::Down(ref value) => value.name()
+            MfType::Down(ref value) => value.name(),
+            MfType::Gaussian(ref value) => value.name()
+        }
+    }
+
+    pub fn shape(&self) -> Shape<T> {
+        match *self {
+            MfType::Triangle(ref v) => Shape::Triangle(v.a, v.b, v.c),
+            MfType::Trapezoid(ref v) => Shape::Trapezoid(v.a, v.b, v.c, v.d),
+            MfType::Up(ref v) => Shape::Up(v.a, v.b),
+            MfType::Down(ref v) => Shape::Down(v.a, v.b),
+            MfType::Gaussian(ref v) => Shape::Gaussian(v.mean, v.sigma)
+        }
+    }
+}
+
+// Name-less copy of an MF, for places that cannot hold a `String`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Shape<T = f32> {
+    Triangle(T, T, T),
+    Trapezoid(T, T, T, T),
+    Up(T, T),
+    Down(T, T),
+    Gaussian(T, T)
+}
+
+impl<T: Float> Shape<T> {
+    pub fn compute(&self, x: T) -> T {
+        match *self {
+            Shape::Triangle(a, b, c) => triangle(a, b, c, x),
+            Shape::Trapezoid(a, b, c, d) => trapezoid(a, b, c, d, x),
+            Shape::Up(a, b) => up(a, b, x),
+            Shape::Down(a, b) => T::one() - up(a, b, x),
+            Shape::Gaussian(mean, sigma) => gaussian(mean, sigma, x)
         }
     }
+}
 
-    
+impl<T: Copy> Shape<T> {
+    // Interval where the membership is 1; `None` where it extends without bound.
+    pub fn core(&self) -> (Option<T>, Option<T>) {
+        match *self {
+            Shape::Triangle(_, b, _) => (Some(b), Some(b)),
+            Shape::Trapezoid(_, b, c, _) => (Some(b), Some(c)),
+            Shape::Up(_, b) => (Some(b), None),
+            Shape::Down(a, _) => (None, Some(a)),
+            Shape::Gaussian(mean, _) => (Some(mean), Some(mean))
+        }
+    }
+
+    // Interval outside which the membership is 0, like `core`.
+    pub fn support(&self) -> (Option<T>, Option<T>) {
+        match *self {
+            Shape::Triangle(a, _, c) => (Some(a), Some(c)),
+            Shape::Trapezoid(a, _, _, d) => (Some(a), Some(d)),
+            Shape::Up(a, _) => (Some(a), None),
+            Shape::Down(_, b) => (None, Some(b)),
+            Shape::Gaussian(..) => (None, None)
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct Triangle {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Triangle<T = f32> {
     name: String,
-    a: f32,
-    b: f32,
-    c: f32
+    a: T,
+    b: T,
+    c: T
 }
 
-impl Triangle {
-    pub fn new(name: &str, init: Vec<f32>) -> MfType {
+impl<T: Float> Triangle<T> {
+    pub fn new(name: &str, init: Vec<T>) -> MfType<T> {
         if init.len() != 3 {
             panic!("init var for Triangle needs 3 values");
         }
@@ -57,29 +127,28 @@ impl Triangle {
         };
         MfType::Triangle(triangle)
     }
-    
-    fn compute(&self, x: f32) -> f32 {
-        let g1 = (x - self.a) / (self.b - self.a);
-        let g2 = (self.c - x) / (self.c - self.a);
-        0f32.max(g1.min(g2))
+
+    fn compute(&self, x: T) -> T {
+        triangle(self.a, self.b, self.c, x)
     }
-    
+
     fn name(&self) -> &str {
         &self.name
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct Trapezoid {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trapezoid<T = f32> {
     name: String,
-    a: f32,
-    b: f32,
-    c: f32,
-    d: f32
+    a: T,
+    b: T,
+    c: T,
+    d: T
 }
 
-impl Trapezoid {
-    pub fn new(name: &str, init: Vec<f32>) -> MfType {
+impl<T: Float> Trapezoid<T> {
+    pub fn new(name: &str, init: Vec<T>) -> MfType<T> {
         if init.len() != 4 {
             panic!("init var for Trapezoid needs 3 values");
         }
@@ -92,11 +161,9 @@ impl Trapezoid {
         };
         MfType::Trapezoid(trapezoid)
     }
-    
-    fn compute(&self, x: f32) -> f32 {
-        let g1 = (x - self.a) / (self.b - self.a);
-        let g2 = (self.d - x) / (self.d - self.c);
-        g1.min(g2).min(1f32).max(0f32)
+
+    fn compute(&self, x: T) -> T {
+        trapezoid(self.a, self.b, self.c, self.d, x)
     }
 
     fn name(&self) -> &str {
@@ -105,14 +172,15 @@ impl Trapezoid {
 }
 
 #[derive(Debug, Clone)]
-pub struct Up {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Up<T = f32> {
     name: String,
-    a: f32,
-    b: f32
+    a: T,
+    b: T
 }
 
-impl Up {
-    pub fn new(name: &str, init: Vec<f32>) -> MfType {
+impl<T: Float> Up<T> {
+    pub fn new(name: &str, init: Vec<T>) -> MfType<T> {
         if init.len() != 2 {
             panic!("init var for Up needs 2 values");
         }
@@ -124,32 +192,26 @@ impl Up {
         MfType::Up(up)
     }
 
-    fn compute(&self, x: f32) -> f32 {
-        if x < self.a {
-            return 0f32
-        }
-        if x > self.b {
-            return 1f32
-        }
-       (x - self.a) / (self.b - self.a)
-        
+    fn compute(&self, x: T) -> T {
+        up(self.a, self.b, x)
     }
 
     fn name(&self) -> &str {
         &self.name
     }
-    
+
 }
 
 #[derive(Debug, Clone)]
-pub struct Down {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Down<T = f32> {
     name: String,
-    a: f32,
-    b: f32
+    a: T,
+    b: T
 }
 
-impl Down {
-    pub fn new(name: &str, init: Vec<f32>) -> MfType {
+impl<T: Float> Down<T> {
+    pub fn new(name: &str, init: Vec<T>) -> MfType<T> {
         if init.len() != 2 {
             panic!("init var for Up needs 2 values");
         }
@@ -161,13 +223,69 @@ impl Down {
         MfType::Down(down)
     }
 
-    fn compute(&self, x: f32) -> f32 {
-        let up = Up::new(&self.name, vec![self.a, self.b]);
-        1f32 - up.compute(x)
-        
+    fn compute(&self, x: T) -> T {
+        T::one() - up(self.a, self.b, x)
     }
-    
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// Parameters are the mean and the standard deviation.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Gaussian<T = f32> {
+    name: String,
+    mean: T,
+    sigma: T
+}
+
+impl<T: Float> Gaussian<T> {
+    pub fn new(name: &str, init: Vec<T>) -> MfType<T> {
+        if init.len() != 2 {
+            panic!("init var for Gaussian needs 2 values");
+        }
+        let gaussian = Gaussian {
+            name: name.to_owned(),
+            mean: init[0],
+            sigma: init[1]
+        };
+        MfType::Gaussian(gaussian)
+    }
+
+    fn compute(&self, x: T) -> T {
+        gaussian(self.mean, self.sigma, x)
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 }
+
+fn triangle<T: Float>(a: T, b: T, c: T, x: T) -> T {
+    let g1 = (x - a) / (b - a);
+    let g2 = (c - x) / (c - b);
+    T::zero().max(g1.min(g2))
+}
+
+fn trapezoid<T: Float>(a: T, b: T, c: T, d: T, x: T) -> T {
+    let g1 = (x - a) / (b - a);
+    let g2 = (d - x) / (d - c);
+    g1.min(g2).min(T::one()).max(T::zero())
+}
+
+fn gaussian<T: Float>(mean: T, sigma: T, x: T) -> T {
+    let d = (x - mean) / sigma;
+    (T::zero() - d * d / T::from_f64(2.0)).exp()
+}
+
+fn up<T: Float>(a: T, b: T, x: T) -> T {
+    if x < a {
+        return T::zero()
+    }
+    if x > b {
+        return T::one()
+    }
+    (x - a) / (b - a)
+}
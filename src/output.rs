@@ -0,0 +1,104 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use alloc::vec::Vec;
+use policy::InferenceError;
+use float::Float;
+use Engine;
+
+// Aggregated output set sampled at `start`, `start + 1`, ... over the output universe:
+// the maximum of the rule outputs, each the hedged output term scaled by the rule's strength.
+// Empty sets give NaN from the defuzzifiers and `None` from `support`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FuzzyOutput<T = f32> {
+    start: usize,
+    samples: Vec<T>
+}
+
+impl<T: Float> FuzzyOutput<T> {
+    pub fn new(start: usize, samples: Vec<T>) -> FuzzyOutput<T> {
+        FuzzyOutput {
+            start,
+            samples
+        }
+    }
+
+    fn points(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.samples.iter().enumerate().map(move |(idx, &m)| (T::from_usize(self.start + idx), m))
+    }
+
+    // Matches the engine's `centroid` only where rule outputs do not overlap; see `deffuz::Centroid`.
+    pub fn centroid(&self) -> T {
+        let mut first = T::zero();
+        let mut second = T::zero();
+        for (y, m) in self.points() {
+            first += y * m;
+            second += m;
+        }
+        first / second
+    }
+
+    // Mean of the points where the set reaches its height. The engine's `mom` is the
+    // mean of rule maxima instead; see `deffuz::Mom`.
+    pub fn mean_of_maxima(&self) -> T {
+        let height = self.height();
+        let mut sum = T::zero();
+        let mut count = 0;
+        for (y, m) in self.points() {
+            if m > T::zero() && m == height {
+                sum += y;
+                count += 1;
+            }
+        }
+        sum / T::from_usize(count)
+    }
+
+    // First point at which the area to its left reaches half of the total.
+    pub fn bisector(&self) -> T {
+        let total = self.samples.iter().fold(T::zero(), |a, &b| a + b);
+        if total <= T::zero() {
+            return T::nan();
+        }
+        let mut area = T::zero();
+        for (y, m) in self.points() {
+            area += m;
+            if area + area >= total {
+                return y;
+            }
+        }
+        T::nan()
+    }
+
+    pub fn height(&self) -> T {
+        self.samples.iter().fold(T::zero(), |a, &b| a.max(b))
+    }
+
+    pub fn support(&self) -> Option<(T, T)> {
+        let mut points = self.points().filter(|&(_, m)| m > T::zero()).map(|(y, _)| y);
+        let first = points.next()?;
+        Some((first, points.last().unwrap_or(first)))
+    }
+
+    pub fn to_samples(&self) -> Vec<(T, T)> {
+        self.points().collect()
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // The same set whatever defuzzification type the engine uses. Inputs are indexed as
+    // returned by `input_index`; the input policies apply as in `evaluate`.
+    pub fn infer(&self, inputs: &[T]) -> Result<FuzzyOutput<T>, InferenceError> {
+        let mut scratch = self.scratch();
+        self.fuzzify_into(inputs, &mut scratch)?;
+        let (start, _) = self.plan.range();
+        Ok(FuzzyOutput::new(start, self.aggregate(&scratch)))
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use mf::{self, MfType, Shape};
+use float::Float;
+use {Engine, InputVar, OutputVar};
+
+// Quality of the terms of one variable, sampled at `resolution` points of its universe.
+// `epsilon` is the lowest of the highest memberships, so the partition is ε-complete
+// for any ε up to it. `overlap` holds, for each pair of neighbouring terms in universe
+// order, the length their supports share over the length of the narrower one.
+// `ruspini_error` is the largest distance of the summed memberships from 1, and
+// `outside` lists the terms that are 0 over the whole universe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PartitionReport<T = f32> {
+    pub var: String,
+    pub universe: (T, T),
+    pub coverage: T,
+    pub uncovered: Vec<(T, T)>,
+    pub epsilon: T,
+    pub overlap: Vec<(String, String, T)>,
+    pub ruspini_error: T,
+    pub ordered: bool,
+    pub degenerate: Vec<String>,
+    pub outside: Vec<String>
+}
+
+impl<T: Float> PartitionReport<T> {
+    pub fn is_complete(&self, epsilon: T) -> bool {
+        self.epsilon >= epsilon
+    }
+
+    pub fn is_ruspini(&self, tolerance: T) -> bool {
+        self.ruspini_error <= tolerance
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.uncovered.is_empty() && self.ordered && self.degenerate.is_empty() && self.outside.is_empty()
+    }
+}
+
+// Parameters out of order, or equal where the MF divides by their difference.
+fn degenerate<T: Float>(shape: Shape<T>) -> bool {
+    let valid = match shape {
+        Shape::Triangle(a, b, c) => a < b && b < c,
+        Shape::Trapezoid(a, b, c, d) => a < b && b <= c && c < d,
+        Shape::Up(a, b) | Shape::Down(a, b) => a < b,
+        Shape::Gaussian(_, sigma) => sigma > T::zero()
+    };
+    !valid
+}
+
+// Point the term is centred on, for ordering: the middle of its core within the universe.
+fn centre<T: Float>(shape: Shape<T>, (start, end): (T, T)) -> T {
+    let (a, b) = shape.core();
+    let a = a.unwrap_or(start).max(start).min(end);
+    let b = b.unwrap_or(end).max(start).min(end);
+    (a + b) / T::from_f64(2.0)
+}
+
+pub fn report<T: Float>(var: &str, terms: &[MfType<T>], universe: (T, T), resolution: usize) -> PartitionReport<T> {
+    if resolution < 2 {
+        panic!("Partition resolution must be at least 2");
+    }
+    let (start, end) = universe;
+    let mut report = PartitionReport {
+        var: var.to_owned(),
+        universe,
+        coverage: T::zero(),
+        uncovered: Vec::new(),
+        epsilon: T::one(),
+        overlap: Vec::new(),
+        ruspini_error: T::zero(),
+        ordered: true,
+        degenerate: terms.iter().filter(|mf| degenerate(mf.shape())).map(|mf| mf.name().to_owned()).collect(),
+        outside: terms.iter().filter(|mf| match mf.shape().support() {
+            (Some(a), _) if a >= end => true,
+            (_, Some(b)) if b <= start => true,
+            _ => false
+        }).map(|mf| mf.name().to_owned()).collect()
+    };
+
+    let mut covered = 0;
+    let mut gap: Option<(T, T)> = None;
+    for idx in 0..resolution {
+        let x = start + (end - start) * T::from_usize(idx) / T::from_usize(resolution - 1);
+        let mut height = T::zero();
+        let mut sum = T::zero();
+        for mf in terms {
+            let m = mf.compute(x);
+            let m = if m.is_nan() { T::zero() } else { m };
+            height = height.max(m);
+            sum += m;
+        }
+        report.epsilon = report.epsilon.min(height);
+        report.ruspini_error = report.ruspini_error.max((sum - T::one()).abs());
+        if height > T::zero() {
+            covered += 1;
+            if let Some(interval) = gap.take() {
+                report.uncovered.push(interval);
+            }
+        }
+        else {
+            gap = Some((gap.map(|x| x.0).unwrap_or(x), x));
+        }
+    }
+    if let Some(interval) = gap {
+        report.uncovered.push(interval);
+    }
+    report.coverage = T::from_usize(covered) / T::from_usize(resolution);
+
+    let centres: Vec<T> = terms.iter().map(|mf| centre(mf.shape(), universe)).collect();
+    report.ordered = centres.windows(2).all(|x| x[0] <= x[1]);
+    let mut order: Vec<usize> = (0..terms.len()).collect();
+    order.sort_by(|&a, &b| centres[a].partial_cmp(&centres[b]).unwrap_or(core::cmp::Ordering::Equal));
+    for pair in order.windows(2) {
+        let (a, b) = (terms[pair[0]].shape().support(), terms[pair[1]].shape().support());
+        let (a0, a1) = (a.0.unwrap_or(start).max(start), a.1.unwrap_or(end).min(end));
+        let (b0, b1) = (b.0.unwrap_or(start).max(start), b.1.unwrap_or(end).min(end));
+        let shared = (a1.min(b1) - a0.max(b0)).max(T::zero());
+        let narrower = (a1 - a0).min(b1 - b0);
+        let ratio = if narrower > T::zero() { shared / narrower } else { T::zero() };
+        report.overlap.push((terms[pair[0]].name().to_owned(), terms[pair[1]].name().to_owned(), ratio));
+    }
+    report
+}
+
+// Evenly spaced terms over [start, end], the first centred on `start` and the last on `end`.
+// Triangles and trapezoids reach past the universe so the end terms are 1 at its bounds,
+// and every point has memberships summing to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Uniform {
+    Triangles,
+    // Cores and slopes of equal width, cores touching both bounds.
+    Trapezoids,
+    // Neighbours cross at 0.5; their sum is close to but not exactly 1.
+    Gaussians,
+    // Triangles with `down` and `up` as the first and last terms.
+    Shouldered
+}
+
+pub fn uniform<T: Float>(kind: Uniform, names: &[&str], start: usize, end: usize) -> Vec<MfType<T>> {
+    if names.len() < 2 {
+        panic!("A uniform partition needs at least 2 terms");
+    }
+    let n = names.len();
+    let (start, end) = (T::from_usize(start), T::from_usize(end));
+    let last = n - 1;
+    names.iter().enumerate().map(|(idx, name)| match kind {
+        Uniform::Trapezoids => {
+            let width = (end - start) / T::from_usize(2 * n - 1);
+            let b = start + width * T::from_usize(2 * idx);
+            mf::Trapezoid::new(name, vec![b - width, b, b + width, b + width * T::from_f64(2.0)])
+        },
+        _ => {
+            let step = (end - start) / T::from_usize(last);
+            let peak = start + step * T::from_usize(idx);
+            match kind {
+                Uniform::Gaussians => mf::Gaussian::new(name, vec![peak, step / (T::from_f64(2.0) * T::from_f64(2.0 * core::f64::consts::LN_2).sqrt())]),
+                Uniform::Shouldered if idx == 0 => mf::Down::new(name, vec![peak, peak + step]),
+                Uniform::Shouldered if idx == last => mf::Up::new(name, vec![peak - step, peak]),
+                _ => mf::Triangle::new(name, vec![peak - step, peak, peak + step])
+            }
+        }
+    }).collect()
+}
+
+impl<T: Float> InputVar<T> {
+    pub fn partition_report(&self, name: &str, resolution: usize) -> PartitionReport<T> {
+        report(name, &self.vars, (T::from_usize(self.start), T::from_usize(self.end)), resolution)
+    }
+
+    pub fn uniform(kind: Uniform, names: &[&str], start: usize, end: usize) -> InputVar<T> {
+        let mut var = InputVar::new(uniform(kind, names, start, end));
+        var.start(start);
+        var.end(end);
+        var
+    }
+
+    pub fn uniform_triangles(names: &[&str], start: usize, end: usize) -> InputVar<T> {
+        InputVar::uniform(Uniform::Triangles, names, start, end)
+    }
+
+    pub fn uniform_trapezoids(names: &[&str], start: usize, end: usize) -> InputVar<T> {
+        InputVar::uniform(Uniform::Trapezoids, names, start, end)
+    }
+
+    pub fn uniform_gaussians(names: &[&str], start: usize, end: usize) -> InputVar<T> {
+        InputVar::uniform(Uniform::Gaussians, names, start, end)
+    }
+
+    pub fn uniform_shouldered(names: &[&str], start: usize, end: usize) -> InputVar<T> {
+        InputVar::uniform(Uniform::Shouldered, names, start, end)
+    }
+}
+
+impl<T: Float> OutputVar<T> {
+    pub fn partition_report(&self, name: &str, resolution: usize) -> PartitionReport<T> {
+        report(name, &self.vars, (T::from_usize(self.start), T::from_usize(self.end)), resolution)
+    }
+
+    pub fn uniform(kind: Uniform, names: &[&str], start: usize, end: usize) -> OutputVar<T> {
+        let mut var = OutputVar::new(uniform(kind, names, start, end));
+        var.start(start);
+        var.end(end);
+        var
+    }
+
+    pub fn uniform_triangles(names: &[&str], start: usize, end: usize) -> OutputVar<T> {
+        OutputVar::uniform(Uniform::Triangles, names, start, end)
+    }
+
+    pub fn uniform_trapezoids(names: &[&str], start: usize, end: usize) -> OutputVar<T> {
+        OutputVar::uniform(Uniform::Trapezoids, names, start, end)
+    }
+
+    pub fn uniform_gaussians(names: &[&str], start: usize, end: usize) -> OutputVar<T> {
+        OutputVar::uniform(Uniform::Gaussians, names, start, end)
+    }
+
+    pub fn uniform_shouldered(names: &[&str], start: usize, end: usize) -> OutputVar<T> {
+        OutputVar::uniform(Uniform::Shouldered, names, start, end)
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // Inputs in `input_index` order, then outputs by name.
+    pub fn partition_reports(&self, resolution: usize) -> Vec<PartitionReport<T>> {
+        let inputs = self.input_names.iter().map(|name| self.inputs[name].partition_report(name, resolution));
+        let outputs = self.output.iter().map(|(name, var)| var.partition_report(name, resolution));
+        inputs.chain(outputs).collect()
+    }
+}
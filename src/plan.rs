@@ -0,0 +1,244 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::borrow::ToOwned;
+use deffuz::{DefuzzOp, Operators, Rule};
+use hedges::Hedge;
+use mf;
+use float::Float;
+use policy::Resolved;
+
+// Rules compiled against interned input indices. Output MFs are sampled once over
+// the output range, consequent hedges applied, so evaluation does no lookups and,
+// given a `Scratch`, no allocation.
+
+#[derive(Debug, Clone)]
+struct Antecedent<T> {
+    input: usize,
+    mf: mf::MfType<T>,
+    hedge: Option<Box<Hedge<T>>>,
+    op: Option<DefuzzOp>
+}
+
+#[derive(Debug, Clone)]
+struct PlanRule<T> {
+    first: usize,
+    last: usize,
+    variable: String,
+    output: mf::MfType<T>,
+    hedge: Option<Box<Hedge<T>>>
+}
+
+impl<T: Float> PlanRule<T> {
+    fn sample(&self, y: T) -> T {
+        match self.hedge {
+            Some(ref hedge) => hedge.apply(self.output.compute(y), &self.output, y),
+            None => self.output.compute(y)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plan<T = f32> {
+    antecedents: Vec<Antecedent<T>>,
+    rules: Vec<PlanRule<T>>,
+    samples: Vec<T>,
+    used: Vec<bool>,
+    start: usize,
+    end: usize
+}
+
+impl<T: Float> Plan<T> {
+    pub fn new() -> Plan<T> {
+        Plan::default()
+    }
+
+    // `variable` is the output variable the rule concludes on.
+    pub fn push<F: Fn(&str) -> usize>(&mut self, rule: &Rule<T>, variable: &str, input_index: F) {
+        let first = self.antecedents.len();
+        for var in &rule.0 {
+            let input = input_index(&var.0);
+            if input >= self.used.len() {
+                self.used.resize(input + 1, false);
+            }
+            self.used[input] = true;
+            self.antecedents.push(Antecedent {
+                input,
+                mf: var.1.clone(),
+                hedge: var.2.clone(),
+                op: var.3
+            });
+        }
+        let rule = PlanRule {
+            first,
+            last: self.antecedents.len(),
+            variable: variable.to_owned(),
+            output: rule.2.clone(),
+            hedge: rule.1.clone()
+        };
+        let (start, end) = (self.start, self.end);
+        self.samples.extend((start..end).map(|y| rule.sample(T::from_usize(y))));
+        self.rules.push(rule);
+    }
+
+    pub fn set_range(&mut self, start: usize, end: usize) {
+        if (start, end) == (self.start, self.end) {
+            return;
+        }
+        self.start = start;
+        self.end = end;
+        self.samples.clear();
+        for rule in &self.rules {
+            self.samples.extend((start..end).map(|y| rule.sample(T::from_usize(y))));
+        }
+    }
+
+    pub fn range(&self) -> (usize, usize) {
+        (self.start, self.end)
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn output(&self, rule: usize) -> &str {
+        &self.rules[rule].variable
+    }
+
+    // Output variable of the last rule, which the range is taken from.
+    pub fn destination(&self) -> &str {
+        self.rules.last().map_or("", |rule| &rule.variable)
+    }
+
+    pub fn uses(&self, input: usize) -> bool {
+        self.used.get(input).cloned().unwrap_or(false)
+    }
+
+    // Output MF of `rule`, hedged, sampled at `start + idx`.
+    pub fn sample(&self, rule: usize, idx: usize) -> T {
+        self.samples[rule * self.sample_count() + idx]
+    }
+
+    // Memberships of every antecedent, hedges applied, in plan order.
+    pub fn fuzzify(&self, inputs: &[T], scratch: &mut Scratch<T>) {
+        scratch.memberships.resize(self.antecedents.len(), T::zero());
+        for (val, ant) in scratch.memberships.iter_mut().zip(self.antecedents.iter()) {
+            let x = inputs[ant.input];
+            *val = ant.mf.compute(x);
+            if let Some(ref hedge) = ant.hedge {
+                *val = hedge.apply(*val, &ant.mf, x);
+            }
+        }
+    }
+
+    // Like `fuzzify`, with each input given by `resolve` from its index.
+    pub fn fuzzify_with<F: Fn(usize) -> Resolved<T>>(&self, scratch: &mut Scratch<T>, resolve: F) {
+        scratch.memberships.resize(self.antecedents.len(), T::zero());
+        for (val, ant) in scratch.memberships.iter_mut().zip(self.antecedents.iter()) {
+            *val = match resolve(ant.input) {
+                Resolved::Value(x) => match ant.hedge {
+                    Some(ref hedge) => hedge.apply(ant.mf.compute(x), &ant.mf, x),
+                    None => ant.mf.compute(x)
+                },
+                Resolved::Degree(x) => match ant.hedge {
+                    Some(ref hedge) => hedge.compute(x),
+                    None => x
+                }
+            };
+        }
+    }
+
+    // Memberships of the antecedents of `rule`, as left by `fuzzify`.
+    pub fn memberships<'a>(&self, rule: usize, scratch: &'a Scratch<T>) -> &'a [T] {
+        let rule = &self.rules[rule];
+        &scratch.memberships[rule.first..rule.last]
+    }
+
+    // Antecedents combined with their operators, as used by the max-membership defuzzifiers.
+    pub fn strength(&self, rule: usize, scratch: &Scratch<T>, ops: &Operators) -> T {
+        let rule = &self.rules[rule];
+        let mut val = T::zero();
+        let mut prev_val = T::zero();
+        let mut oper = &None;
+        for idx in rule.first..rule.last {
+            val = scratch.memberships[idx];
+            if let Some(ref op) = *oper {
+                val = ops.apply(op, prev_val, val);
+            }
+            oper = &self.antecedents[idx].op;
+            prev_val = val;
+        }
+        val
+    }
+
+    // Number of rules whose antecedents hold to a non-zero degree.
+    pub fn fired(&self, scratch: &Scratch<T>, ops: &Operators) -> usize {
+        (0..self.rules.len()).filter(|&rule| self.strength(rule, scratch, ops) > T::zero()).count()
+    }
+
+    // Hedged output MF of `rule` at sample `idx` scaled by `strength`.
+    pub fn imply(&self, rule: usize, idx: usize, strength: T) -> T {
+        self.sample(rule, idx) * strength
+    }
+
+    // Output of `rule` at sample `idx`, with every antecedent implied on the
+    // output MF before the operators are applied, as the centroid does.
+    pub fn activation(&self, rule: usize, idx: usize, scratch: &Scratch<T>, ops: &Operators) -> T {
+        let sample = self.sample(rule, idx);
+        let rule = &self.rules[rule];
+        let mut prev_val = T::zero();
+        let mut oper = &None;
+        let mut output = T::zero();
+        for idx in rule.first..rule.last {
+            output = sample * scratch.memberships[idx];
+            if let Some(ref op) = *oper {
+                output = ops.apply(op, prev_val, output);
+            }
+            oper = &self.antecedents[idx].op;
+            prev_val = output;
+        }
+        output
+    }
+
+    pub fn scratch(&self) -> Scratch<T> {
+        Scratch {
+            memberships: vec![T::zero(); self.antecedents.len()],
+            previous: None
+        }
+    }
+}
+
+// Reusable buffers for evaluating a plan. Only grows, so evaluation is
+// allocation-free once it has seen the plan it is used with.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch<T = f32> {
+    memberships: Vec<T>,
+    previous: Option<T>
+}
+
+impl<T: Float> Scratch<T> {
+    pub fn new() -> Scratch<T> {
+        Scratch::default()
+    }
+
+    // Last value returned by an evaluation that used this scratch.
+    pub fn previous(&self) -> Option<T> {
+        self.previous
+    }
+
+    pub fn set_previous(&mut self, value: T) {
+        self.previous = Some(value);
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use alloc::string::String;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InferenceError {
+    UndefinedDefuzz,
+    NoRuleFired,
+    MissingInput(String),
+    OutOfRange(String)
+}
+
+impl fmt::Display for InferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            InferenceError::UndefinedDefuzz => write!(f, "Cannot use 'Undefined' to defuzzify!"),
+            InferenceError::NoRuleFired => write!(f, "No rule fired"),
+            InferenceError::MissingInput(ref name) => write!(f, "Variable {} not defined.", name),
+            InferenceError::OutOfRange(ref name) => write!(f, "Variable {} is outside its universe", name)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for InferenceError {}
+
+// What to return when no rule fires, for outputs without a default of their own.
+// `Nan` is the historical behaviour; `Previous` uses the last value produced with the
+// same `Scratch`, or by `calculate`, and fails if there is none.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum NoFirePolicy<T = f32> {
+    #[default]
+    Nan,
+    Error,
+    Default(T),
+    Previous
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outcome<T = f32> {
+    pub value: T,
+    pub fired_rules: usize
+}
+
+// `Extrapolate` evaluates the MFs outside the universe given to `add_input_var`,
+// as the engine always did.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum RangePolicy {
+    #[default]
+    Extrapolate,
+    Clamp,
+    Reject
+}
+
+// Missing inputs are absent from the `HashMap` given to `calculate`, or NaN or past the end of slices.
+// `Unknown` gives every term of the input the same membership degree.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum MissingPolicy<T = f32> {
+    #[default]
+    Error,
+    Default(T),
+    Unknown(T)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputPolicy<T = f32> {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub range: RangePolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub missing: MissingPolicy<T>
+}
+
+// An input after its policy has been applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolved<T = f32> {
+    Value(T),
+    Degree(T)
+}
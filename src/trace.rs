@@ -0,0 +1,145 @@
+// Copyright 2015 Threat X, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::boxed::Box;
+use alloc::borrow::ToOwned;
+use deffuz::{DefuzzType, DefuzzOp};
+use hedges::Hedge;
+use policy::{InferenceError, Resolved};
+use float::Float;
+use mf;
+use Engine;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VarTrace<T = f32> {
+    pub name: String,
+    pub value: T,
+    pub terms: Vec<(String, T)>
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AntecedentTrace<T = f32> {
+    pub input: String,
+    pub hedges: Vec<String>,
+    pub term: String,
+    pub degree: T,
+    pub op: Option<DefuzzOp>
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuleTrace<T = f32> {
+    pub rule: String,
+    pub antecedents: Vec<AntecedentTrace<T>>,
+    pub output: String,
+    pub hedges: Vec<String>,
+    pub term: String,
+    pub strength: T,
+    pub activation: Vec<T>
+}
+
+// Sets are sampled at `start`, `start + 1`, ... over the output range. Each rule's
+// `activation` is its output as the engine's defuzzifier sees it; `aggregate` is the
+// set `infer` returns.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trace<T = f32> {
+    pub inputs: Vec<VarTrace<T>>,
+    pub rules: Vec<RuleTrace<T>>,
+    pub start: usize,
+    pub aggregate: Vec<T>,
+    pub output: VarTrace<T>
+}
+
+fn words<T: Float>(hedge: &Option<Box<Hedge<T>>>) -> Vec<String> {
+    match *hedge {
+        Some(ref hedge) => hedge.words().into_iter().map(|x| x.to_owned()).collect(),
+        None => Vec::new()
+    }
+}
+
+fn var_trace<T: Float>(name: &str, vars: &[mf::MfType<T>], x: T) -> VarTrace<T> {
+    VarTrace {
+        name: name.to_owned(),
+        value: x,
+        terms: vars.iter().map(|mf| (mf.name().to_owned(), mf.compute(x))).collect()
+    }
+}
+
+impl<T: Float> Engine<T> {
+    // Inputs are indexed as returned by `input_index`, and traced after the input
+    // policies are applied. Fails where `evaluate` does, except when no rule fires.
+    pub fn calculate_explained(&self, inputs: &[T]) -> Result<Trace<T>, InferenceError> {
+        let mut scratch = self.scratch();
+        let value = match self.evaluate(inputs, &mut scratch) {
+            Ok(outcome) => outcome.value,
+            Err(InferenceError::NoRuleFired) => T::nan(),
+            Err(e) => return Err(e)
+        };
+
+        let input_traces = self.input_names.iter().enumerate().map(|(idx, name)| {
+            let vars = &self.inputs[name].vars;
+            let x = self.input_value(inputs, idx);
+            match self.resolve(idx, x) {
+                Ok(Resolved::Value(x)) => var_trace(name, vars, x),
+                Ok(Resolved::Degree(degree)) => VarTrace {
+                    name: name.clone(),
+                    value: x,
+                    terms: vars.iter().map(|mf| (mf.name().to_owned(), degree)).collect()
+                },
+                Err(_) => var_trace(name, vars, x)
+            }
+        }).collect();
+
+        let (start, _) = self.plan.range();
+        let samples = self.plan.sample_count();
+        let rules: Vec<RuleTrace<T>> = (0..self.plan.rule_count()).map(|rule| {
+            let strength = self.plan.strength(rule, &scratch, &self.operators);
+            let activation: Vec<T> = (0..samples).map(|idx| match self.defuzz {
+                DefuzzType::Mom(_) => self.plan.imply(rule, idx, strength),
+                _ => self.plan.activation(rule, idx, &scratch, &self.operators)
+            }).collect();
+            let (ref vars, ref hedge, ref output) = self.rules[rule];
+            let antecedents = vars.iter().zip(self.plan.memberships(rule, &scratch).iter()).map(|(var, &degree)| AntecedentTrace {
+                input: var.0.clone(),
+                hedges: words(&var.2),
+                term: var.1.name().to_owned(),
+                degree,
+                op: var.3
+            }).collect();
+            RuleTrace {
+                rule: self.sources[rule].clone(),
+                antecedents,
+                output: self.plan.output(rule).to_owned(),
+                hedges: words(hedge),
+                term: output.name().to_owned(),
+                strength,
+                activation
+            }
+        }).collect();
+
+        let destination = self.plan.destination();
+        let output = match self.output.get(destination) {
+            Some(var) => var_trace(destination, &var.vars, value),
+            None => var_trace("", &[], value)
+        };
+        Ok(Trace {
+            inputs: input_traces,
+            rules,
+            start,
+            aggregate: self.aggregate(&scratch),
+            output
+        })
+    }
+}
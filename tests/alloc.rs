@@ -0,0 +1,55 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Counts the allocations made on the current thread, so other tests running in parallel
+// do not show up in the count.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|x| x.get())
+}
+
+#[test]
+fn calculate_into_does_not_allocate() {
+    for defuzz in ["centroid", "mom"].iter() {
+        let engine = common::builder()
+            .rule("if var1 is very low or var1 is slightly high then output is somewhat low")
+            .defuzz(defuzz)
+            .build()
+            .unwrap();
+        let mut scratch = engine.scratch();
+        let before = allocations();
+        for step in 0..100 {
+            let value = engine.calculate_into(&[step as f32 + 0.5], &mut scratch);
+            assert!(!value.is_nan());
+        }
+        assert_eq!(allocations(), before, "{} allocates", defuzz);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::hedges::HedgeKind;
+
+fn low(power: f32) -> Vec<f32> {
+    let low = &common::terms::<f32>()[0];
+    (0..100).map(|y| low.compute(y as f32).powf(power)).collect()
+}
+
+fn label(result: Option<(String, f32)>) -> String {
+    let (label, score) = result.unwrap();
+    assert!(score > 0.999, "{}: {}", label, score);
+    label
+}
+
+#[test]
+fn approximates_hedged_labels() {
+    let engine = common::engine();
+    let output = engine.output_var("output").unwrap();
+    assert_eq!(label(output.approximate(&engine, &low(1.0))), "low");
+    assert_eq!(label(output.approximate(&engine, &low(2.0))), "very low");
+
+    let set = low(2.0);
+    let centroid = set.iter().enumerate().map(|(y, &m)| y as f32 * m).sum::<f32>() / set.iter().sum::<f32>();
+    assert_eq!(label(output.approximate_value(&engine, centroid)), "very low");
+}
+
+#[test]
+fn approximates_with_engine_hedges() {
+    let mut engine = common::engine();
+    engine.add_hedge("barely", HedgeKind::Power(1.5));
+    let output = engine.output_var("output").unwrap();
+    assert_eq!(label(output.approximate(&engine, &low(1.5))), "barely low");
+
+    // `not low` is not a label, so the complement is matched by a hedged `high` at best.
+    let set: Vec<f32> = low(1.0).iter().map(|m| 1.0 - m).collect();
+    let (label, _) = output.approximate(&engine, &set).unwrap();
+    assert!(label.ends_with("high"), "{}", label);
+}
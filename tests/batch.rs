@@ -0,0 +1,28 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, InputVar, Layout, OutputVar};
+
+// Runs on the sequential path by default and on the rayon one with `--features rayon`.
+#[test]
+fn batch_matches_calculate_into() {
+    let engine = Engine::builder()
+        .input("var1", InputVar::new(common::terms()), 0, 100)
+        .input("var2", InputVar::new(common::terms()), 0, 100)
+        .output("output", OutputVar::new(common::terms()), 0, 100)
+        .rule("if var1 is low and var2 is high then output is low")
+        .rule("if var1 is high or var2 is low then output is high")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let rows: Vec<[f32; 2]> = (0..500).map(|i| [(i % 97) as f32 + 0.5, (i % 89) as f32 + 0.25]).collect();
+    let mut scratch = engine.scratch();
+    let expected: Vec<f32> = rows.iter().map(|row| engine.calculate_into(row, &mut scratch)).collect();
+
+    let row_major: Vec<f32> = rows.iter().flat_map(|row| row.iter().cloned()).collect();
+    assert_eq!(engine.calculate_batch(&row_major, Layout::RowMajor), expected);
+
+    let column_major: Vec<f32> = (0..2).flat_map(|j| rows.iter().map(move |row| row[j])).collect();
+    assert_eq!(engine.calculate_batch(&column_major, Layout::ColumnMajor), expected);
+}
@@ -0,0 +1,34 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::BuildError;
+
+#[test]
+fn builds_ready_engine() {
+    let engine = common::builder().defuzz("centroid").build().unwrap();
+    let value = engine.calculate_into(&[20.0], &mut engine.scratch());
+    assert!(!value.is_nan());
+    assert_eq!(engine.input_index("var1"), Some(0));
+}
+
+#[test]
+fn rejects_missing_defuzz() {
+    assert_eq!(common::builder().build().unwrap_err(), BuildError::NoDefuzz);
+    assert_eq!(common::builder().defuzz("median").build().unwrap_err(), BuildError::InvalidDefuzz("median".to_owned()));
+}
+
+#[test]
+fn rejects_undefined_variable_in_rule() {
+    let err = common::builder().rule("if var2 is low then output is low").defuzz("centroid").build().unwrap_err();
+    match err {
+        BuildError::InvalidRule(2, _) => (),
+        _ => panic!("unexpected error: {}", err)
+    }
+}
+
+#[test]
+fn legacy_engine_without_defuzz_gives_nan() {
+    let engine = common::engine();
+    assert!(engine.calculate_into(&[20.0], &mut engine.scratch()).is_nan());
+}
@@ -0,0 +1,65 @@
+// Fixtures shared by the integration tests. Each test file only uses some of them.
+#![allow(dead_code)]
+
+use rsfuzzy::{Engine, EngineBuilder, Float, InputVar, OutputVar};
+use rsfuzzy::mf::{self, MfType};
+
+// var1 and output both split 0..100 into `low` and `high`, meeting at 50 where neither holds.
+pub const SRC: &str = "
+input var1 [0, 100]
+    low: down(0, 50)
+    high: up(50, 100)
+output output [0, 100]
+    low: down(0, 50)
+    high: up(50, 100)
+defuzz centroid
+rules
+    if var1 is low then output is low
+    if var1 is high then output is high
+";
+
+pub const RULES: [&str; 2] = ["if var1 is low then output is low", "if var1 is high then output is high"];
+
+pub fn terms<T: Float>() -> Vec<MfType<T>> {
+    vec![
+        mf::Down::new("low", vec![T::zero(), T::from_f64(50.0)]),
+        mf::Up::new("high", vec![T::from_f64(50.0), T::from_f64(100.0)])
+    ]
+}
+
+// The engines below are the same as `SRC`, but leave the defuzzification type to the test.
+pub fn builder() -> EngineBuilder {
+    builder_with_float()
+}
+
+pub fn builder_with_float<T: Float>() -> EngineBuilder<T> {
+    Engine::builder_with_float()
+        .input("var1", InputVar::new(terms()), 0, 100)
+        .output("output", OutputVar::new(terms()), 0, 100)
+        .rules(RULES.to_vec())
+}
+
+pub fn engine() -> Engine {
+    engine_with_float()
+}
+
+pub fn engine_with_float<T: Float>() -> Engine<T> {
+    let mut engine = Engine::with_float();
+    engine.add_input_var("var1", InputVar::new(terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(terms()), 0, 100);
+    engine.add_rules(RULES.to_vec());
+    engine
+}
+
+// Like `engine`, but nothing fires for var1 in (40, 60).
+pub fn gap_engine() -> Engine {
+    let terms = || vec![
+        mf::Down::new("low", vec![0.0, 40.0]),
+        mf::Up::new("high", vec![60.0, 100.0])
+    ];
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", InputVar::new(terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(terms()), 0, 100);
+    engine.add_rules(RULES.to_vec());
+    engine
+}
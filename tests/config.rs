@@ -0,0 +1,51 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::Engine;
+use common::SRC;
+
+#[test]
+fn parses_engine() {
+    let engine = Engine::from_config(SRC).unwrap();
+    assert!(!engine.calculate_into(&[20.0], &mut engine.scratch()).is_nan());
+}
+
+#[test]
+fn rejects_duplicate_input() {
+    let src = SRC.replace("output output [0, 100]", "input var1 [0, 100]\n    mid: up(0, 10)\noutput output [0, 100]");
+    let err = Engine::from_config(&src).unwrap_err();
+    assert_eq!(err.line, 5);
+    assert!(err.message.contains("more than once"), "{}", err);
+}
+
+#[test]
+fn rejects_duplicate_output() {
+    let src = SRC.replace("defuzz centroid", "output output [0, 10]\ndefuzz centroid");
+    let err = Engine::from_config(&src).unwrap_err();
+    assert_eq!(err.line, 8);
+    assert!(err.message.contains("more than once"), "{}", err);
+}
+
+#[test]
+fn rejects_missing_defuzz() {
+    let src = SRC.replace("defuzz centroid\n", "");
+    let err = Engine::from_config(&src).unwrap_err();
+    assert!(err.message.contains("defuzzification"), "{}", err);
+}
+
+#[test]
+fn rejects_empty_range() {
+    let src = SRC.replace("output output [0, 100]", "output output [100, 100]");
+    let err = Engine::from_config(&src).unwrap_err();
+    assert_eq!(err.line, 5);
+    assert!(err.message.contains("empty range"), "{}", err);
+}
+
+#[test]
+fn rejects_variable_without_terms() {
+    let src = SRC.replace("output output [0, 100]", "input var2 [0, 10]\noutput output [0, 100]");
+    let err = Engine::from_config(&src).unwrap_err();
+    assert_eq!(err.line, 5);
+    assert!(err.message.contains("no terms"), "{}", err);
+}
@@ -0,0 +1,28 @@
+extern crate rsfuzzy;
+
+mod common;
+
+#[test]
+fn controller_matches_engine() {
+    for defuzz in ["centroid", "mom"].iter() {
+        let engine = common::builder()
+            .rule("if var1 is very low or var1 is somewhat high then output is extremely low")
+            .defuzz(defuzz)
+            .build()
+            .unwrap();
+        let controller = engine.compile_fixed::<1, 4, 2>().unwrap();
+        let mut scratch = engine.scratch();
+        for step in 0..100 {
+            let x = step as f32 + 0.5;
+            let expected = engine.calculate_into(&[x], &mut scratch);
+            let result = controller.calculate(&[x]);
+            assert!((result - expected).abs() < 1e-3, "{} at {}: {} differs from {}", defuzz, x, result, expected);
+        }
+    }
+}
+
+#[test]
+fn controller_rejects_too_many_rules() {
+    let engine = common::builder().defuzz("centroid").build().unwrap();
+    assert_eq!(engine.compile_fixed::<1, 1, 2>().unwrap_err(), rsfuzzy::controller::ControllerError::TooManyRules);
+}
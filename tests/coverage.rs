@@ -0,0 +1,25 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, Gap, InputVar, OutputVar};
+
+#[test]
+fn finds_known_gap() {
+    let engine = common::gap_engine();
+    let coverage = engine.coverage(11, 0.1);
+    assert_eq!(coverage.gaps, vec![Gap { bounds: vec![(35.0, 65.0)], strength: 0.0 }]);
+    assert_eq!(coverage.min_strength, 0.0);
+    assert!(coverage.unreferenced.is_empty());
+    assert!(!coverage.is_complete());
+}
+
+#[test]
+fn finds_unreferenced_terms() {
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", InputVar::new(common::terms()), 0, 100);
+    engine.add_input_var("var2", InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(common::terms()), 0, 100);
+    engine.add_rules(vec!["if var1 is low and var2 is low then output is low", "if var1 is high and var2 is not low then output is high"]);
+    assert_eq!(engine.unreferenced(), vec![vec!["low", "high"], vec!["high", "low"]]);
+}
@@ -0,0 +1,31 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, Explainer, InputVar, OutputVar};
+
+#[test]
+fn explains_fixed_input() {
+    let engine = Engine::builder()
+        .input("var1", InputVar::new(common::terms()), 0, 100)
+        .input("var2", InputVar::new(common::terms()), 0, 100)
+        .output("output", OutputVar::new(common::terms()), 0, 100)
+        .rule("if var1 is low or var2 is very low then output is low")
+        .rule("if var1 is high or var2 is high then output is high")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let trace = engine.calculate_explained(&[80.0, 20.0]).unwrap();
+    assert_eq!(trace.explain(), "output is high (0.15) mainly because var1 is high (0.60) or var2 is high (0.00); \
+        also because var1 is low (0.00) or var2 is very low (0.36)");
+    assert_eq!(Explainer::new().top(1).precision(3).explain(&trace),
+        "output is high (0.147) mainly because var1 is high (0.600) or var2 is high (0.000)");
+}
+
+#[test]
+fn explains_no_rule_fired() {
+    let mut engine = common::gap_engine();
+    engine.add_defuzz("centroid");
+    let trace = engine.calculate_explained(&[50.0]).unwrap();
+    assert_eq!(trace.explain(), "output is undefined because no rule fired");
+}
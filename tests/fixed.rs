@@ -0,0 +1,97 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, BuiltEngine, FixedError, Q16};
+use rsfuzzy::fixed;
+use rsfuzzy::mf::Shape;
+
+fn engine(end: usize) -> BuiltEngine {
+    let x = end as f32;
+    Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "low", vec![0.0, 0.5 * x]),
+            ("trapezoid", "mid", vec![0.2 * x, 0.4 * x, 0.6 * x, 0.8 * x]),
+            ("up", "high", vec![0.5 * x, x])
+        ], 0, end)
+        .output("output", fz_output_var![
+            ("down", "low", vec![0.0, 0.5 * x]),
+            ("trapezoid", "mid", vec![0.2 * x, 0.4 * x, 0.6 * x, 0.8 * x]),
+            ("up", "high", vec![0.5 * x, x])
+        ], 0, end)
+        .rule("if var1 is low then output is high")
+        .rule("if var1 is mid then output is very mid")
+        .rule("if var1 is high then output is low")
+        .defuzz("centroid")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn matches_float_engine() {
+    for &end in [100, 16000].iter() {
+        let f_engine = engine(end);
+        let q_engine = f_engine.compile_q16().unwrap();
+        let mut scratch = f_engine.scratch();
+        for step in 0..=20 {
+            let x = (end * step / 20) as f32;
+            let expected = f_engine.calculate_into(&[x], &mut scratch);
+            let result = q_engine.calculate(&[Q16::from_float(x)]).unwrap().to_float::<f32>();
+            assert!((result - expected).abs() <= 1e-3 * end as f32, "{}: {} differs from {}", x, result, expected);
+        }
+    }
+}
+
+#[test]
+fn sums_many_rules_without_overflow() {
+    let rules = vec!["if var1 is low then output is high"; 64];
+    let f_engine = Engine::builder()
+        .input("var1", fz_input_var![("down", "low", vec![0.0, 16000.0])], 0, 16000)
+        .output("output", fz_output_var![("up", "high", vec![0.0, 16000.0])], 0, 16000)
+        .rules(rules)
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let q_engine = f_engine.compile_q16().unwrap();
+    let expected = f_engine.calculate_into(&[0.0], &mut f_engine.scratch());
+    let result = q_engine.calculate(&[Q16::from_int(0)]).unwrap().to_float::<f32>();
+    assert!((result - expected).abs() <= 16.0, "{} differs from {}", result, expected);
+}
+
+#[test]
+fn rejects_values_past_q16() {
+    assert_eq!(engine(40000).compile_q16().unwrap_err(), FixedError::UniverseOutOfRange("var1".to_owned()));
+
+    let mut f_engine = Engine::new();
+    f_engine.add_input_var("var1", fz_input_var![("down", "low", vec![0.0, 20000.0])], 0, 100);
+    f_engine.add_output_var("output", fz_output_var![("down", "low", vec![0.0, 50.0])], 0, 100);
+    f_engine.add_rules(vec!["if var1 is low then output is low"]);
+    f_engine.add_defuzz("centroid");
+    assert_eq!(f_engine.compile_q16().unwrap_err(), FixedError::TermOutOfRange("var1".to_owned(), "low".to_owned()));
+}
+
+#[test]
+fn rejects_undefined_defuzz() {
+    assert_eq!(common::engine().compile_q16().unwrap_err(), FixedError::UndefinedDefuzz);
+}
+
+#[test]
+fn rejects_gaussian_terms() {
+    let f_engine = Engine::builder()
+        .input("var1", fz_input_var![("gaussian", "mid", vec![50.0, 10.0])], 0, 100)
+        .output("output", fz_output_var![("down", "low", vec![0.0, 50.0])], 0, 100)
+        .rule("if var1 is mid then output is low")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    assert_eq!(f_engine.compile_q16().unwrap_err(), FixedError::UnsupportedTerm("var1".to_owned(), "mid".to_owned()));
+}
+
+#[test]
+fn triangle_matches_float() {
+    let shape = Shape::Triangle(Q16::from_int(20), Q16::from_int(40), Q16::from_int(80));
+    assert_eq!(fixed::compute(&shape, Q16::from_int(40)), Q16::ONE);
+    assert_eq!(fixed::compute(&shape, Q16::from_int(60)), Q16::from_float(0.5f32));
+    assert_eq!(fixed::compute(&shape, Q16::from_int(80)), Q16::ZERO);
+}
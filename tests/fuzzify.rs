@@ -0,0 +1,23 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::hedges::Hedge;
+
+#[test]
+fn fuzzifies_every_term() {
+    let engine = common::engine();
+    assert_eq!(engine.fuzzify("var1", 20.0), Some(vec![("low", 0.6), ("high", 0.0)]));
+    assert_eq!(engine.fuzzify("var1", 75.0), Some(vec![("low", 0.0), ("high", 0.5)]));
+    assert_eq!(engine.fuzzify("var2", 20.0), None);
+}
+
+#[test]
+fn fuzzifies_with_hedge() {
+    let engine = common::engine();
+    let very = Hedge::new("very", None);
+    let states = engine.fuzzify_with("var1", 20.0, &very).unwrap();
+    assert_eq!(states[0].0, "low");
+    assert!((states[0].1 - 0.36).abs() < 1e-6, "{}", states[0].1);
+    assert_eq!(states[1], ("high", 0.0));
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+use rsfuzzy::Engine;
+
+// `below` and `not` are hedges as well as term names here.
+fn engine(rules: Vec<&str>) -> Engine {
+    let mut engine = Engine::new();
+    engine.add_input_var("temp", fz_input_var![
+        ("down", "below", vec![0.0, 50.0]),
+        ("up", "high", vec![50.0, 100.0])
+    ], 0, 100);
+    engine.add_output_var("output", fz_output_var![
+        ("down", "low", vec![0.0, 50.0]),
+        ("up", "not", vec![50.0, 100.0])
+    ], 0, 100);
+    engine.add_rules(rules);
+    engine.add_defuzz("centroid");
+    engine
+}
+
+#[test]
+fn terms_may_be_named_like_hedges() {
+    let named = engine(vec!["if temp is below then output is low", "if temp is very below then output is not"]);
+    let trace = named.calculate_explained(&[20.0]).unwrap();
+    assert_eq!((trace.rules[0].antecedents[0].term.as_str(), trace.rules[0].term.as_str()), ("below", "low"));
+    assert!(trace.rules[0].antecedents[0].hedges.is_empty());
+    assert_eq!(trace.rules[1].antecedents[0].hedges, ["very"]);
+    assert_eq!((trace.rules[1].antecedents[0].term.as_str(), trace.rules[1].term.as_str()), ("below", "not"));
+    assert_eq!(trace.rules[0].strength, 0.6);
+}
+
+#[test]
+fn hedges_still_apply_before_terms() {
+    let hedged = engine(vec!["if temp is below high then output is not low"]);
+    let trace = hedged.calculate_explained(&[20.0]).unwrap();
+    assert_eq!(trace.rules[0].antecedents[0].hedges, ["below"]);
+    assert_eq!(trace.rules[0].antecedents[0].term, "high");
+    assert_eq!(trace.rules[0].hedges, ["not"]);
+    assert_eq!(trace.rules[0].term, "low");
+}
@@ -0,0 +1,36 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, InputVar, OutputVar};
+
+fn unruled() -> Engine {
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(common::terms()), 0, 100);
+    engine.add_defuzz("centroid");
+    engine
+}
+
+#[test]
+fn learns_known_rule_base() {
+    let mut engine = unruled();
+    // Samples of y = x, with an outlier at 30 that loses to the stronger low/low samples
+    // and one at 50, where no term holds, that is skipped.
+    let samples = vec![(vec![10.0], 10.0), (vec![20.0], 20.0), (vec![30.0], 90.0), (vec![90.0], 90.0), (vec![80.0], 85.0), (vec![50.0], 50.0)];
+    let rules = engine.generate_rules("output", &samples);
+    let text: Vec<String> = rules.iter().map(|rule| rule.to_rule()).collect();
+    assert_eq!(text, common::RULES.to_vec());
+    assert_eq!(rules.iter().map(|rule| rule.samples).collect::<Vec<_>>(), vec![3, 2]);
+    assert!((rules[0].degree - 0.64).abs() < 1e-6);
+    assert!((rules[1].degree - 0.64).abs() < 1e-6);
+
+    engine.add_generated_rules(&rules);
+    let mut reference = common::engine();
+    reference.add_defuzz("centroid");
+    let (mut a, mut b) = (engine.scratch(), reference.scratch());
+    for step in 0..100 {
+        let x = step as f32 + 0.5;
+        assert_eq!(engine.calculate_into(&[x], &mut a), reference.calculate_into(&[x], &mut b));
+    }
+}
@@ -0,0 +1,62 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, InputVar, Issue, OutputVar};
+use rsfuzzy::mf;
+
+fn engine(rules: Vec<&str>) -> Engine {
+    let mut terms = common::terms();
+    terms.push(mf::Triangle::new("mid", vec![25.0, 50.0, 75.0]));
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", InputVar::new(terms), 0, 100);
+    engine.add_input_var("var2", InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(common::terms()), 0, 100);
+    engine.add_rules(rules);
+    engine
+}
+
+#[test]
+fn clean_engine() {
+    assert!(common::engine().lint().is_empty());
+}
+
+#[test]
+fn finds_unused_terms() {
+    let issues = engine(vec!["if var1 is low and var2 is low then output is low", "if var1 is high then output is high"]).lint();
+    assert_eq!(issues, vec![
+        Issue::UnusedTerm { var: "var1".to_owned(), term: "mid".to_owned() },
+        Issue::UnusedTerm { var: "var2".to_owned(), term: "high".to_owned() }
+    ]);
+}
+
+#[test]
+fn finds_duplicate_rule() {
+    let mut rules = common::RULES.to_vec();
+    rules.push("if var1 is low then output is low");
+    let issues = engine(rules).lint();
+    assert_eq!(issues[0], Issue::Duplicate { rule: 2, of: 0 });
+    assert!(!issues.iter().any(|x| matches!(*x, Issue::Conflict { .. } | Issue::Subsumed { .. })));
+}
+
+#[test]
+fn finds_conflicting_rule() {
+    let mut rules = common::RULES.to_vec();
+    rules.push("if var1 is high then output is low");
+    let issues = engine(rules).lint();
+    match issues[0] {
+        Issue::Conflict { rule: 2, with: 1, distance } => assert!(distance > 0.5 && distance < 1.0),
+        ref x => panic!("expected a conflict, got {:?}", x)
+    }
+    assert!(!issues.iter().any(|x| matches!(*x, Issue::Duplicate { .. })));
+}
+
+#[test]
+fn finds_subsumed_rule() {
+    let mut rules = common::RULES.to_vec();
+    rules.push("if var1 is low and var2 is high then output is low");
+    rules.push("if var1 is high and var2 is low then output is low");
+    let issues = engine(rules).lint();
+    assert_eq!(issues[0], Issue::Subsumed { rule: 2, by: 0 });
+    assert!(!issues.iter().any(|x| matches!(*x, Issue::Subsumed { rule: 3, .. })));
+}
@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::Engine;
+
+#[test]
+fn max_error_matches_sampled_engine() {
+    let engine = Engine::builder()
+        .input("var1", fz_input_var![
+            ("down", "low", vec![0.0, 60.0]),
+            ("up", "high", vec![40.0, 100.0])
+        ], 0, 100)
+        .output("output", rsfuzzy::OutputVar::new(common::terms()), 0, 100)
+        .rules(common::RULES.to_vec())
+        .rule("if var1 is very low then output is somewhat high")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let table = engine.compile_lut(11);
+    let mut scratch = engine.scratch();
+    let mut expected = 0.0f32;
+    for cell in 0..10 {
+        let x = cell as f32 * 10.0 + 5.0;
+        expected = expected.max((table.calculate(&[x]) - engine.calculate_into(&[x], &mut scratch)).abs());
+    }
+    assert!(expected > 0.0);
+    assert_eq!(table.max_error(), expected);
+}
+
+#[test]
+fn max_error_counts_cells_without_rules() {
+    let mut engine = common::gap_engine();
+    engine.add_defuzz("centroid");
+    let table = engine.compile_lut(3);
+    assert!(table.calculate(&[20.0]).is_nan());
+    assert!(!engine.calculate_into(&[20.0], &mut engine.scratch()).is_nan());
+    assert_eq!(table.max_error(), f32::INFINITY);
+}
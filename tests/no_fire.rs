@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+mod common;
+
+use std::collections::HashMap;
+use rsfuzzy::{Engine, InferenceError, NoFirePolicy};
+
+fn engine() -> Engine {
+    let mut engine = common::gap_engine();
+    engine.add_defuzz("centroid");
+    engine
+}
+
+fn inputs(x: f32) -> HashMap<String, f32> {
+    fz_set_inputs![("var1", x)]
+}
+
+#[test]
+fn calculate_falls_back_to_previous() {
+    let mut engine = engine();
+    engine.set_no_fire_policy(NoFirePolicy::Previous);
+    assert!(engine.calculate(inputs(50.0)).is_nan());
+    let first = engine.calculate(inputs(90.0));
+    assert!(!first.is_nan());
+    assert_eq!(engine.calculate(inputs(50.0)), first);
+}
+
+#[test]
+fn output_default_overrides_policy() {
+    let mut engine = engine();
+    engine.set_no_fire_policy(NoFirePolicy::Error);
+    let mut scratch = engine.scratch();
+    assert_eq!(engine.evaluate(&[50.0], &mut scratch).unwrap_err(), InferenceError::NoRuleFired);
+
+    engine.set_output_default("output", 42.0);
+    let outcome = engine.evaluate(&[50.0], &mut scratch).unwrap();
+    assert_eq!((outcome.value, outcome.fired_rules), (42.0, 0));
+    assert_eq!(engine.calculate(inputs(50.0)), 42.0);
+}
+
+#[test]
+fn builder_output_default() {
+    let built = Engine::builder()
+        .input("var1", fz_input_var![("down", "low", vec![0.0, 40.0])], 0, 100)
+        .output("output", fz_output_var![("down", "low", vec![0.0, 40.0])], 0, 100)
+        .rule("if var1 is low then output is low")
+        .output_default("output", 7.0)
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    assert_eq!(built.calculate_into(&[80.0], &mut built.scratch()), 7.0);
+}
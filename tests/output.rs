@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate rsfuzzy;
+
+use rsfuzzy::{Engine, BuiltEngine};
+
+fn engine(defuzz: &str) -> BuiltEngine {
+    Engine::builder()
+        .input("var1", fz_input_var![
+            ("trapezoid", "medium", vec![30.0, 40.0, 45.0, 55.0]),
+            ("trapezoid", "high", vec![40.0, 60.0, 65.0, 75.0])
+        ], 0, 100)
+        .output("output", fz_output_var![
+            ("trapezoid", "medium", vec![30.0, 40.0, 45.0, 55.0]),
+            ("trapezoid", "high", vec![40.0, 60.0, 65.0, 75.0])
+        ], 0, 100)
+        .rule("if var1 is medium then output is medium")
+        .rule("if var1 is high then output is high")
+        .defuzz(defuzz)
+        .build()
+        .unwrap()
+}
+
+// Only medium fires at 35, so there is nothing for the aggregation to combine.
+#[test]
+fn centroid_matches_engine_for_one_rule() {
+    let f_engine = engine("centroid");
+    let expected = f_engine.calculate_into(&[35.0], &mut f_engine.scratch());
+    let value = f_engine.infer(&[35.0]).unwrap().centroid();
+    assert!((value - expected).abs() < 1e-4, "{} != {}", value, expected);
+}
+
+// Both rules fire at 48, medium to 0.7 and high to 0.4. The set is their maximum,
+// whichever defuzzification type the engine uses.
+#[test]
+fn aggregates_by_maximum() {
+    let set = engine("centroid").infer(&[48.0]).unwrap();
+    assert_eq!(set.to_samples(), engine("mom").infer(&[48.0]).unwrap().to_samples());
+    assert!((set.height() - 0.7).abs() < 1e-6, "{}", set.height());
+    assert_eq!(set.mean_of_maxima(), 42.5);
+    assert_eq!(set.support(), Some((31.0, 74.0)));
+}
@@ -0,0 +1,48 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::mf::{self, Shape};
+use rsfuzzy::partition::{self, Uniform};
+
+#[test]
+fn triangle_falls_from_peak() {
+    let tri = mf::Triangle::new("mid", vec![20.0f32, 40.0, 80.0]);
+    assert_eq!(tri.compute(40.0), 1.0);
+    assert_eq!(tri.compute(60.0), 0.5);
+    assert_eq!(tri.compute(80.0), 0.0);
+    assert_eq!(tri.compute(30.0), 0.5);
+    assert_eq!(tri.compute(90.0), 0.0);
+}
+
+#[test]
+fn uniform_triangles_are_triangles() {
+    let terms = partition::uniform::<f32>(Uniform::Triangles, &["low", "mid", "high"], 0, 100);
+    assert_eq!(terms[1].shape(), Shape::Triangle(0.0, 50.0, 100.0));
+    let report = partition::report("var1", &terms, (0.0, 100.0), 101);
+    assert!(report.is_valid());
+    assert!(report.is_ruspini(1e-6), "{}", report.ruspini_error);
+    assert_eq!(report.epsilon, 0.5);
+}
+
+#[test]
+fn reports_non_ruspini_partition() {
+    let terms = vec![mf::Down::new("low", vec![0.0f32, 60.0]), mf::Up::new("high", vec![40.0, 100.0])];
+    let report = partition::report("var1", &terms, (0.0, 100.0), 101);
+    assert!(report.is_valid());
+    assert!(!report.is_ruspini(0.1));
+    assert!((report.ruspini_error - 2.0 / 3.0).abs() < 1e-6, "{}", report.ruspini_error);
+    assert!((report.epsilon - 1.0 / 6.0).abs() < 1e-6, "{}", report.epsilon);
+    assert!((report.overlap[0].2 - 1.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn reports_gap_between_terms() {
+    let report = partition::report("var1", &common::terms::<f32>(), (0.0, 100.0), 101);
+    assert!(!report.is_valid());
+    assert!(!report.is_ruspini(0.1));
+    assert_eq!(report.uncovered, vec![(50.0, 50.0)]);
+    assert_eq!(report.ruspini_error, 1.0);
+    assert_eq!(report.epsilon, 0.0);
+    assert_eq!(report.overlap, vec![("low".to_owned(), "high".to_owned(), 0.0)]);
+}
@@ -0,0 +1,60 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, InferenceError, InputPolicy, InputVar, OutputVar, MissingPolicy, RangePolicy};
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", InputVar::new(common::terms()), 0, 100);
+    engine.add_input_var("var2", InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(common::terms()), 0, 100);
+    engine.add_rules(vec![
+        "if var1 is low and var2 is low then output is low",
+        "if var1 is high or var2 is high then output is high"
+    ]);
+    engine.add_defuzz("centroid");
+    engine
+}
+
+#[test]
+fn short_slice_is_missing_input() {
+    let mut engine = engine();
+    let mut scratch = engine.scratch();
+    assert_eq!(engine.evaluate(&[10.0], &mut scratch).unwrap_err(), InferenceError::MissingInput("var2".to_owned()));
+    assert!(engine.calculate_into(&[10.0], &mut scratch).is_nan());
+
+    engine.set_input_policy("var2", InputPolicy {
+        range: RangePolicy::Extrapolate,
+        missing: MissingPolicy::Default(30.0)
+    });
+    let expected = engine.calculate_into(&[10.0, 30.0], &mut scratch);
+    assert_eq!(engine.calculate_into(&[10.0], &mut scratch), expected);
+    assert_eq!(engine.infer(&[10.0]).unwrap().to_samples(), engine.infer(&[10.0, 30.0]).unwrap().to_samples());
+}
+
+#[test]
+fn infer_applies_input_policies() {
+    let mut engine = engine();
+    engine.set_input_policy("var1", InputPolicy {
+        range: RangePolicy::Reject,
+        missing: MissingPolicy::Error
+    });
+    assert_eq!(engine.infer(&[200.0, 50.0]).unwrap_err(), InferenceError::OutOfRange("var1".to_owned()));
+    assert_eq!(engine.infer(&[50.0]).unwrap_err(), InferenceError::MissingInput("var2".to_owned()));
+}
+
+#[test]
+fn explained_inputs_are_resolved() {
+    let mut engine = engine();
+    assert_eq!(engine.calculate_explained(&[10.0]).unwrap_err(), InferenceError::MissingInput("var2".to_owned()));
+
+    engine.set_input_policy("var1", InputPolicy {
+        range: RangePolicy::Clamp,
+        missing: MissingPolicy::Error
+    });
+    let trace = engine.calculate_explained(&[150.0, 20.0]).unwrap();
+    assert_eq!(trace.inputs[0].value, 100.0);
+    assert_eq!(trace.inputs[0].terms[1], ("high".to_owned(), 1.0));
+    assert_eq!(trace.output.value, engine.calculate_into(&[100.0, 20.0], &mut engine.scratch()));
+}
@@ -0,0 +1,29 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::Engine;
+
+#[test]
+fn f64_engines() {
+    let mut engine = common::engine_with_float::<f64>();
+    engine.add_defuzz("centroid");
+    let built = common::builder_with_float::<f64>().defuzz("centroid").build().unwrap();
+    let parsed = Engine::<f64>::from_config_with_float(common::SRC).unwrap();
+
+    let expected: f64 = engine.calculate_into(&[20.0], &mut engine.scratch());
+    assert!(!expected.is_nan());
+    assert_eq!(built.calculate_into(&[20.0], &mut built.scratch()), expected);
+    assert_eq!(parsed.calculate_into(&[20.0], &mut parsed.scratch()), expected);
+}
+
+#[test]
+fn new_engines_are_f32() {
+    let mut engine = Engine::new();
+    engine.add_input_var("var1", rsfuzzy::InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", rsfuzzy::OutputVar::new(common::terms()), 0, 100);
+    engine.add_rules(common::RULES.to_vec());
+    engine.add_defuzz("centroid");
+    let value: f32 = engine.calculate_into(&[20.0], &mut engine.scratch());
+    assert!(!value.is_nan());
+}
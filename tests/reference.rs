@@ -0,0 +1,75 @@
+extern crate rsfuzzy;
+
+use rsfuzzy::Engine;
+use rsfuzzy::hedges::Hedge;
+
+fn vars() -> Vec<rsfuzzy::mf::MfType> {
+    vec![
+        rsfuzzy::mf::Down::new("low", vec![20.0, 50.0]),
+        rsfuzzy::mf::Trapezoid::new("mid", vec![20.0, 40.0, 60.0, 80.0]),
+        rsfuzzy::mf::Up::new("high", vec![50.0, 80.0])
+    ]
+}
+
+// Mamdani system with product implication, summed rule outputs and a discrete centroid
+// over 0..100, checked against values computed independently of the crate.
+#[test]
+fn matches_reference_fis() {
+    let mut f_engine = Engine::new();
+    f_engine.add_input_var("var1", rsfuzzy::InputVar::new(vars()), 0, 100);
+    f_engine.add_input_var("var2", rsfuzzy::InputVar::new(vars()), 0, 100);
+    f_engine.add_output_var("output", rsfuzzy::OutputVar::new(vars()), 0, 100);
+    f_engine.add_rules(vec![
+        "if var1 is very low and var2 is low then output is very low",
+        "if var1 is not very mid then output is somewhat mid",
+        "if var1 is high or var2 is very not high then output is indeed high",
+        "if var2 is mid then output is very not low"
+    ]);
+    f_engine.add_defuzz("centroid");
+
+    let reference = [
+        ([35.0, 70.0], 62.908_23),
+        ([10.0, 10.0], 50.390_59),
+        ([55.0, 45.0], 73.870_04),
+        ([90.0, 20.0], 63.491_15),
+        ([65.0, 85.0], 64.534_9)
+    ];
+    let mut scratch = f_engine.scratch();
+    for &(inputs, expected) in reference.iter() {
+        let result = f_engine.calculate_into(&inputs, &mut scratch);
+        assert!((result - expected).abs() < 1e-3, "{:?}: {} differs from {}", inputs, result, expected);
+    }
+}
+
+// The inner hedge used to be skipped for a membership of 0, so very(not(0)) gave 0.
+#[test]
+fn inner_hedge_applies_at_zero() {
+    let hedge: Hedge = Hedge::new("very", Some(Box::new(Hedge::new("not", None))));
+    assert_eq!(hedge.compute(0.0), 1.0);
+
+    let mut f_engine = Engine::new();
+    f_engine.add_input_var("var1", rsfuzzy::InputVar::new(vars()), 0, 100);
+    f_engine.add_output_var("output", rsfuzzy::OutputVar::new(vars()), 0, 100);
+    f_engine.add_rules(vec!["if var1 is very not high then output is low"]);
+    f_engine.add_defuzz("centroid");
+    let trace = f_engine.calculate_explained(&[10.0]).unwrap();
+    assert_eq!(trace.rules[0].strength, 1.0);
+}
+
+// `then output is very low` squares the term, not the product of term and strength.
+#[test]
+fn consequent_hedge_applies_before_implication() {
+    let mut f_engine = Engine::new();
+    f_engine.add_input_var("var1", rsfuzzy::InputVar::new(vars()), 0, 100);
+    f_engine.add_output_var("output", rsfuzzy::OutputVar::new(vars()), 0, 100);
+    f_engine.add_rules(vec!["if var1 is mid then output is very low"]);
+    f_engine.add_defuzz("centroid");
+    let trace = f_engine.calculate_explained(&[30.0]).unwrap();
+    let rule = &trace.rules[0];
+    assert_eq!(rule.strength, 0.5);
+    let low = &vars()[0];
+    for (y, &activation) in rule.activation.iter().enumerate() {
+        let expected = rule.strength * low.compute(y as f32).powf(2.0);
+        assert!((activation - expected).abs() < 1e-6, "{}: {} differs from {}", y, activation, expected);
+    }
+}
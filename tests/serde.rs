@@ -0,0 +1,64 @@
+#![cfg(feature = "serde")]
+
+extern crate rsfuzzy;
+extern crate serde_json;
+
+mod common;
+
+use rsfuzzy::{Engine, InputVar, OutputVar};
+use rsfuzzy::hedges::HedgeKind;
+
+// Inputs are added out of name order.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.add_input_var("zeta", InputVar::new(common::terms()), 0, 100);
+    engine.add_input_var("alpha", InputVar::new(common::terms()), 0, 100);
+    engine.add_output_var("output", OutputVar::new(common::terms()), 0, 100);
+    engine.add_rules(vec![
+        "if zeta is low then output is low",
+        "if alpha is high then output is high"
+    ]);
+    engine.add_defuzz("centroid");
+    engine
+}
+
+#[test]
+fn round_trip_keeps_input_order() {
+    let before = engine();
+    let json = serde_json::to_string(&before).unwrap();
+    let after: Engine = serde_json::from_str(&json).unwrap();
+    assert_eq!(after.input_index("zeta"), Some(0));
+    assert_eq!(after.input_index("alpha"), Some(1));
+    let expected = before.calculate_into(&[10.0, 90.0], &mut before.scratch());
+    let value = after.calculate_into(&[10.0, 90.0], &mut after.scratch());
+    assert!((value - expected).abs() < 1e-4, "{} != {}", value, expected);
+}
+
+#[test]
+fn rejects_unknown_input_name() {
+    let mut json: serde_json::Value = serde_json::to_value(engine()).unwrap();
+    json["input_names"] = serde_json::json!(["zeta", "beta"]);
+    assert!(serde_json::from_value::<Engine>(json).is_err());
+}
+
+// The first rule keeps the built-in `very`; only the one added after the override uses `very^4`.
+#[test]
+fn round_trip_keeps_hedge_order() {
+    let mut before = engine();
+    before.add_rules(vec!["if zeta is very high then output is low"]);
+    before.add_hedge("very", HedgeKind::Power(4.0));
+    before.add_hedge("barely", HedgeKind::Power(1.5));
+    before.add_rules(vec!["if alpha is very low then output is barely high"]);
+    let json = serde_json::to_value(&before).unwrap();
+    assert_eq!(json["hedges"], serde_json::json!([
+        {"name": "very", "kind": {"power": 4.0}, "after_rules": 3},
+        {"name": "barely", "kind": {"power": 1.5}, "after_rules": 3}
+    ]));
+
+    let after: Engine = serde_json::from_value(json).unwrap();
+    for inputs in [[70.0, 20.0], [90.0, 60.0]].iter() {
+        let expected = before.calculate_into(inputs, &mut before.scratch());
+        let value = after.calculate_into(inputs, &mut after.scratch());
+        assert!((value - expected).abs() < 1e-4, "{:?}: {} != {}", inputs, value, expected);
+    }
+}
@@ -0,0 +1,24 @@
+extern crate rsfuzzy;
+
+mod common;
+
+use rsfuzzy::{Engine, InputVar, OutputVar};
+
+// The engine defuzzifies over the output of its last rule, so the trace reports that one.
+#[test]
+fn trace_reports_rule_and_engine_outputs() {
+    let engine = Engine::builder()
+        .input("var1", InputVar::new(common::terms()), 0, 100)
+        .output("output", OutputVar::new(common::terms()), 0, 100)
+        .output("other", OutputVar::new(common::terms()), 0, 100)
+        .rule("if var1 is low then other is high")
+        .rule("if  var1 is high  then   output is low")
+        .defuzz("centroid")
+        .build()
+        .unwrap();
+    let trace = engine.calculate_explained(&[20.0]).unwrap();
+    let outputs: Vec<&str> = trace.rules.iter().map(|rule| rule.output.as_str()).collect();
+    assert_eq!(outputs, ["other", "output"]);
+    assert_eq!(trace.output.name, "output");
+    assert_eq!(trace.output.value, engine.calculate_into(&[20.0], &mut engine.scratch()));
+}